@@ -0,0 +1,205 @@
+//! Analytics types shared by analytics operations and runner sinks
+//!
+//! Workers write data points (Analytics Engine `writeDataPoint`) through
+//! `Operation::BindingAnalytics`, which is fire-and-forget like `Log`. The
+//! runner collects them in an `AnalyticsBuffer` during the execution and
+//! writes them to its store in one batch afterwards.
+
+use crate::AnalyticsLimits;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Data point written by a worker
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsDataPoint {
+    /// Dimensions to group and filter by (strings)
+    #[serde(default)]
+    pub blobs: Vec<String>,
+    /// Numeric values to aggregate
+    #[serde(default)]
+    pub doubles: Vec<f64>,
+    /// Sampling keys (at most one per point by default)
+    #[serde(default)]
+    pub indexes: Vec<String>,
+}
+
+impl AnalyticsDataPoint {
+    /// Total size of the blobs in bytes
+    pub fn blob_bytes(&self) -> usize {
+        self.blobs.iter().map(String::len).sum()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buffered {
+    points: HashMap<String, Vec<AnalyticsDataPoint>>,
+    count: usize,
+    dropped: usize,
+}
+
+/// Per-execution buffer of data points, by binding
+///
+/// Points over the limits are dropped (and counted): writes never fail on
+/// the worker side.
+///
+/// # Example
+///
+/// ```ignore
+/// impl OperationsHandler for MyRunner {
+///     fn handle_binding_analytics(&self, binding: &str, point: AnalyticsDataPoint) {
+///         self.analytics.push(binding, point);
+///     }
+/// }
+///
+/// // After the execution
+/// for (binding, points) in runner.analytics.take() {
+///     store.write(&binding, points).await;
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct AnalyticsBuffer {
+    limits: AnalyticsLimits,
+    state: Mutex<Buffered>,
+}
+
+impl AnalyticsBuffer {
+    /// Create an empty buffer with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty buffer with custom limits
+    pub fn with_limits(limits: AnalyticsLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(Buffered::default()),
+        }
+    }
+
+    /// Buffer a data point, returning whether it was kept
+    pub fn push(&self, binding: &str, point: AnalyticsDataPoint) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if state.count >= self.limits.max_points_per_execution
+            || self.limits.check_point(&point).is_err()
+        {
+            state.dropped += 1;
+            return false;
+        }
+
+        state.count += 1;
+        state
+            .points
+            .entry(binding.to_string())
+            .or_default()
+            .push(point);
+
+        true
+    }
+
+    /// Number of buffered data points
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().count
+    }
+
+    /// Whether no data point is buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of data points dropped for exceeding the limits
+    pub fn dropped(&self) -> usize {
+        self.state.lock().unwrap().dropped
+    }
+
+    /// Take the buffered data points by binding, resetting the buffer
+    pub fn take(&self) -> HashMap<String, Vec<AnalyticsDataPoint>> {
+        std::mem::take(&mut *self.state.lock().unwrap()).points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(blobs: &[&str], doubles: usize, indexes: &[&str]) -> AnalyticsDataPoint {
+        AnalyticsDataPoint {
+            blobs: blobs.iter().map(|blob| blob.to_string()).collect(),
+            doubles: vec![1.0; doubles],
+            indexes: indexes.iter().map(|index| index.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn points_over_limits_are_rejected() {
+        let limits = AnalyticsLimits {
+            max_blobs: 2,
+            max_blob_bytes: 8,
+            max_doubles: 2,
+            max_indexes: 1,
+            max_index_size: 4,
+            ..AnalyticsLimits::default()
+        };
+
+        assert!(
+            limits
+                .check_point(&point(&["abcd", "efgh"], 2, &["idx"]))
+                .is_ok()
+        );
+        assert!(limits.check_point(&AnalyticsDataPoint::default()).is_ok());
+
+        for (point, error) in [
+            (point(&["a", "b", "c"], 0, &[]), "Too many blobs"),
+            (point(&["abcd", "efghi"], 0, &[]), "Blobs too large"),
+            (point(&[], 3, &[]), "Too many doubles"),
+            (point(&[], 0, &["a", "b"]), "Too many indexes"),
+            (point(&[], 0, &["abcde"]), "Index too large"),
+        ] {
+            let err = limits.check_point(&point).unwrap_err();
+            assert!(err.starts_with(error), "{}", err);
+        }
+    }
+
+    #[test]
+    fn buffer_drops_invalid_points() {
+        let buffer = AnalyticsBuffer::new();
+
+        assert!(buffer.push("EVENTS", point(&["a"], 1, &["key"])));
+        assert!(!buffer.push("EVENTS", point(&[], 21, &[])));
+        assert!(!buffer.push("EVENTS", point(&[], 0, &["a", "b"])));
+        assert!(buffer.push("OTHER", point(&[], 0, &[])));
+
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.dropped(), 2);
+
+        let points = buffer.take();
+        assert_eq!(points["EVENTS"], [point(&["a"], 1, &["key"])]);
+        assert_eq!(points["OTHER"].len(), 1);
+    }
+
+    #[test]
+    fn buffer_caps_points_per_execution() {
+        let buffer = AnalyticsBuffer::with_limits(AnalyticsLimits {
+            max_points_per_execution: 3,
+            ..AnalyticsLimits::default()
+        });
+
+        let kept = (0..5)
+            .filter(|_| buffer.push("EVENTS", AnalyticsDataPoint::default()))
+            .count();
+        assert_eq!(kept, 3);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.dropped(), 2);
+
+        // Taking the points resets the counters for the next execution
+        assert_eq!(buffer.take()["EVENTS"].len(), 3);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.dropped(), 0);
+        assert!(buffer.take().is_empty());
+
+        assert!(buffer.push("EVENTS", AnalyticsDataPoint::default()));
+        assert_eq!(buffer.len(), 1);
+    }
+}
@@ -0,0 +1,1392 @@
+//! Filesystem-backed storage and assets
+//!
+//! Maps each binding to a directory on disk. Object keys are relative
+//! paths using `/` as separator (`images/logo.png`).
+//!
+//! Storage semantics:
+//! - `Get`, `GetStream`, `Head` and `Copy` of a missing key return `NotFound`
+//! - `Fetch` of a missing key returns a 404 response
+//! - Object metadata (`PutOptions`) is stored in a JSON sidecar file under
+//!   `.ow-meta/`; size, etag and upload time come from the file itself
+//! - `Put` writes atomically (temporary file + rename) and creates parent
+//!   directories as needed
+//! - `PutStream` writes chunks as they arrive, `GetStream` reads the file in
+//!   chunks; both run file system calls on the blocking thread pool
+//! - `List` walks the directory tree and returns keys in lexicographic order;
+//!   with a delimiter, keys below it are grouped into `delimited_prefixes`;
+//!   truncated lists return a `cursor` to resume from
+//! - Puts verify the supplied `checksums` and fail on mismatch; multipart
+//!   uploads do not accept checksums
+//! - `Copy` copies the file and its metadata sidecar
+//! - `Delete` of missing keys succeeds, empty parent directories are removed
+//! - `Presign` is not supported (there is no URL to sign)
+//! - Multipart upload parts are stored under `.ow-uploads/<upload_id>/` until
+//!   completed or aborted; completed objects get the same
+//!   `<hash of part etags>-<count>` etag as with the SQLite backend
+//!
+//! Assets (`BindingFetch`) serve files from the directory by URL path, with
+//! `index.html` for directory paths. Only `GET` and `HEAD` are allowed;
+//! `Range` and conditional request headers are honored. Paths with a
+//! component starting with `.` (dotfiles such as `.env`, hidden
+//! directories) are not served and return 404.
+//!
+//! Keys containing `..`, absolute paths, backslashes, NUL bytes or path
+//! components starting with `.ow-` (reserved) are rejected, and resolved
+//! paths must stay inside the binding directory (symlinks pointing outside
+//! are refused, before any directory is created).
+
+use super::{
+    ChecksumVerifier, check_checksums, check_completed_parts, check_delete_keys,
+    check_multipart_options, check_part_number, check_part_size, etag, list_page, multipart_etag,
+    new_upload_id, object_response, rejection_response, rejection_result,
+};
+use crate::{
+    ContentRange, GetOptions, HttpMethod, HttpRequest, HttpResponse, ObjectMetadata, OpFuture,
+    OperationsHandler, PutOptions, ResponseBody, StorageObject, StorageOp, StorageResult,
+    StorageStream, UploadedPart,
+};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+
+/// Chunk size for streamed reads
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Channel capacity (in chunks) for streamed reads
+const STREAM_BUFFER_SIZE: usize = 16;
+
+/// Prefix of reserved file names (hidden from `List`, rejected in keys)
+const RESERVED_PREFIX: &str = ".ow-";
+
+/// Prefix of temporary files written by `Put`
+const TMP_PREFIX: &str = ".ow-tmp-";
+
+/// Directory (relative to the binding root) holding metadata sidecars
+const META_DIR: &str = ".ow-meta";
+
+/// Directory (relative to the binding root) holding pending multipart uploads
+const UPLOADS_DIR: &str = ".ow-uploads";
+
+/// Pending multipart upload, stored as `<UPLOADS_DIR>/<upload_id>/upload.json`
+#[derive(Serialize, Deserialize)]
+struct PendingUpload {
+    key: String,
+    options: PutOptions,
+}
+
+/// Filesystem store implementing `handle_binding_storage` and `handle_binding_fetch`
+///
+/// # Example
+///
+/// ```ignore
+/// let backend = FsBackend::new()
+///     .with_binding("ASSETS", "./public")
+///     .with_binding("UPLOADS", "./data/uploads");
+/// ```
+#[derive(Debug, Default)]
+pub struct FsBackend {
+    roots: HashMap<String, PathBuf>,
+}
+
+/// Metadata sidecar of an object
+#[derive(Default, PartialEq, Serialize, Deserialize)]
+struct Sidecar {
+    #[serde(flatten)]
+    options: PutOptions,
+    /// Etag of an object assembled from parts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    multipart: Option<MultipartEtag>,
+}
+
+impl From<PutOptions> for Sidecar {
+    fn from(options: PutOptions) -> Self {
+        Self {
+            options,
+            multipart: None,
+        }
+    }
+}
+
+/// Etag of an object assembled from parts (same format as the SQLite backend)
+///
+/// Only valid while the file keeps the validator it was written with: a file
+/// replaced outside the backend gets its mtime-size etag back.
+#[derive(PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MultipartEtag {
+    etag: String,
+    file_etag: String,
+}
+
+/// File metadata used for headers
+struct FileInfo {
+    size: u64,
+    modified: SystemTime,
+}
+
+impl FileInfo {
+    /// Strong validator derived from mtime and size (like nginx)
+    ///
+    /// Every write replaces the file, so a new body gets a new mtime.
+    fn etag(&self) -> String {
+        let mtime = self
+            .modified
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+
+        format!("{:x}-{:x}", mtime, self.size)
+    }
+
+    /// Multipart etag for this file, if it still applies
+    fn multipart_etag(&self, multipart: Option<MultipartEtag>) -> Option<MultipartEtag> {
+        multipart.filter(|multipart| multipart.file_etag == self.etag())
+    }
+
+    /// Object metadata from file info and its sidecar
+    fn into_metadata(self, key: &str, sidecar: Sidecar) -> ObjectMetadata {
+        let etag = match self.multipart_etag(sidecar.multipart) {
+            Some(multipart) => multipart.etag,
+            None => self.etag(),
+        };
+        let options = sidecar.options;
+
+        ObjectMetadata {
+            key: key.to_string(),
+            size: self.size,
+            etag,
+            uploaded: self
+                .modified
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            http_metadata: options.http_metadata,
+            custom_metadata: options.custom_metadata,
+            checksums: options.checksums,
+        }
+    }
+}
+
+impl FsBackend {
+    /// Create a backend without bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a binding name to a directory
+    ///
+    /// The directory is created on first write if it does not exist.
+    pub fn with_binding(mut self, binding: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        self.roots.insert(binding.into(), root.into());
+        self
+    }
+
+    /// Execute a storage operation against a binding
+    pub async fn execute_storage(&self, binding: &str, op: StorageOp) -> StorageResult {
+        let result = match op {
+            StorageOp::PutStream { key, body, options } => {
+                self.put_stream(binding, &key, body, options).await
+            }
+            op => self.try_execute_storage(binding, op),
+        };
+
+        match result {
+            Ok(result) => result,
+            Err(err) => StorageResult::Error(err),
+        }
+    }
+
+    /// Serve an asset request from a binding directory
+    pub fn fetch_asset(
+        &self,
+        binding: &str,
+        request: &HttpRequest,
+    ) -> Result<HttpResponse, String> {
+        let root = self.root(binding)?;
+
+        if !matches!(request.method, HttpMethod::Get | HttpMethod::Head) {
+            return Ok(HttpResponse {
+                status: 405,
+                headers: vec![("allow".into(), "GET, HEAD".into())],
+                body: ResponseBody::None,
+            });
+        }
+
+        let mut key = match url_path(&request.url) {
+            Some(key) => key,
+            None => return Ok(not_found()),
+        };
+
+        if key.split('/').any(|part| part.starts_with('.')) {
+            return Ok(not_found());
+        }
+
+        if key.is_empty() || key.ends_with('/') {
+            key.push_str("index.html");
+        }
+
+        let path = match resolve(root, &key) {
+            Ok(path) if path.is_dir() => path.join("index.html"),
+            Ok(path) => path,
+            Err(_) => return Ok(not_found()),
+        };
+
+        let Some(key) = key_of(root, &path) else {
+            return Ok(not_found());
+        };
+
+        let options = GetOptions::from_headers(&request.headers);
+
+        self.file_response(root, &key, request.method == HttpMethod::Head, &options)
+    }
+
+    fn root(&self, binding: &str) -> Result<&Path, String> {
+        self.roots
+            .get(binding)
+            .map(PathBuf::as_path)
+            .ok_or_else(|| format!("Binding '{}' not configured", binding))
+    }
+
+    /// Write chunks to disk as they arrive, without buffering the object
+    ///
+    /// File system calls run on the blocking thread pool. The temporary file
+    /// is removed if the write fails or the future is dropped.
+    async fn put_stream(
+        &self,
+        binding: &str,
+        key: &str,
+        mut stream: StorageStream,
+        options: PutOptions,
+    ) -> Result<StorageResult, String> {
+        let root = self.root(binding)?.to_path_buf();
+        let path = resolve(&root, key)?;
+
+        let (tmp, file) = blocking({
+            let (root, path) = (root.clone(), path.clone());
+            move || create_tmp(&root, &path)
+        })
+        .await?;
+
+        let mut file = tokio::fs::File::from_std(file);
+        let mut checksums = ChecksumVerifier::new(&options.checksums);
+        let mut size = 0u64;
+
+        while let Some(chunk) = stream.body.recv().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            checksums.update(&chunk);
+            size += chunk.len() as u64;
+        }
+
+        if let Some(len) = stream.content_length
+            && len != size
+        {
+            return Err(format!(
+                "Body length mismatch: declared {} bytes, received {}",
+                len, size
+            ));
+        }
+
+        checksums.verify()?;
+        file.flush().await.map_err(|e| e.to_string())?;
+        file.sync_all().await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(tmp.path(), &path)
+            .await
+            .map_err(|e| e.to_string())?;
+        tmp.persist();
+
+        let key = key.to_string();
+
+        blocking(move || {
+            write_meta(&root, &key, &options.into())?;
+            Ok(StorageResult::metadata(object_metadata(&root, &key)?))
+        })
+        .await
+    }
+
+    fn try_execute_storage(&self, binding: &str, op: StorageOp) -> Result<StorageResult, String> {
+        let root = self.root(binding)?;
+
+        match op {
+            StorageOp::Get { key, options } => {
+                let path = resolve(root, &key)?;
+
+                let Some(metadata) = object_metadata(root, &key)? else {
+                    return Ok(StorageResult::NotFound);
+                };
+
+                let range = match options.evaluate(&metadata) {
+                    Ok(range) => range,
+                    Err(rejection) => return Ok(rejection_result(metadata, rejection)),
+                };
+
+                // The file may have been removed since its metadata was read
+                let object = read_range(root, &path, range)?.map(|body| StorageObject {
+                    metadata,
+                    range,
+                    body,
+                });
+
+                Ok(object.map_or(StorageResult::NotFound, StorageResult::Object))
+            }
+
+            StorageOp::GetStream { key, options } => {
+                let path = resolve(root, &key)?;
+
+                let Some(metadata) = object_metadata(root, &key)? else {
+                    return Ok(StorageResult::NotFound);
+                };
+
+                let range = match options.evaluate(&metadata) {
+                    Ok(range) => range,
+                    Err(rejection) => return Ok(rejection_result(metadata, rejection)),
+                };
+
+                let object = stream_file(root, &path, range)?.map(|body| StorageObject {
+                    metadata,
+                    range,
+                    body,
+                });
+
+                Ok(object.map_or(StorageResult::NotFound, StorageResult::Stream))
+            }
+
+            StorageOp::Fetch { key, options } => {
+                resolve(root, &key)?;
+                let response = self.file_response(root, &key, false, &options)?;
+                Ok(StorageResult::Response(response))
+            }
+
+            StorageOp::Put { key, body, options } => {
+                let path = resolve(root, &key)?;
+                check_checksums(&options.checksums, &body)?;
+                write_file(root, &path, &body)?;
+                write_meta(root, &key, &options.into())?;
+                Ok(StorageResult::metadata(object_metadata(root, &key)?))
+            }
+
+            StorageOp::PutStream { key, .. } => {
+                Err(format!("Streaming put of '{}' must be awaited", key))
+            }
+
+            StorageOp::Head { key } => {
+                resolve(root, &key)?;
+                Ok(StorageResult::metadata(object_metadata(root, &key)?))
+            }
+
+            StorageOp::List { options } => {
+                let prefix = options.prefix.as_deref().unwrap_or_default();
+
+                let mut keys = Vec::new();
+                collect_keys(root, root, &mut keys)?;
+                keys.retain(|key| key.starts_with(prefix));
+                keys.sort();
+
+                let page = list_page(keys.into_iter().map(Ok), &options)?;
+                let mut objects = Vec::with_capacity(page.keys.len());
+
+                for key in page.keys {
+                    // Skip files removed while listing
+                    if let Some(metadata) = object_metadata(root, &key)? {
+                        objects.push(options.filter_metadata(metadata));
+                    }
+                }
+
+                Ok(StorageResult::List {
+                    objects,
+                    delimited_prefixes: page.delimited_prefixes,
+                    truncated: page.truncated,
+                    cursor: page.cursor,
+                })
+            }
+
+            StorageOp::Copy { from, to } => {
+                let source = resolve(root, &from)?;
+                let target = resolve(root, &to)?;
+
+                let Some(info) = file_info(root, &source)? else {
+                    return Ok(StorageResult::NotFound);
+                };
+
+                let mut sidecar = read_meta(root, &from)?;
+                copy_file(root, &source, &target)?;
+
+                // Keep the multipart etag, for the file of the copy
+                let multipart = info.multipart_etag(sidecar.multipart.take());
+
+                if let (Some(multipart), Some(copy)) = (multipart, file_info(root, &target)?) {
+                    sidecar.multipart = Some(MultipartEtag {
+                        etag: multipart.etag,
+                        file_etag: copy.etag(),
+                    });
+                }
+
+                write_meta(root, &to, &sidecar)?;
+
+                Ok(StorageResult::metadata(object_metadata(root, &to)?))
+            }
+
+            StorageOp::Delete { keys } => {
+                check_delete_keys(&keys)?;
+
+                // Validate every key before deleting any
+                let paths = keys
+                    .iter()
+                    .map(|key| resolve(root, key))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for (key, path) in keys.iter().zip(paths) {
+                    if file_info(root, &path)?.is_none() {
+                        continue;
+                    }
+
+                    fs::remove_file(&path).map_err(|e| e.to_string())?;
+                    remove_empty_parents(root, &path);
+                    write_meta(root, key, &Sidecar::default())?;
+                }
+
+                Ok(StorageResult::Body(None))
+            }
+
+            StorageOp::Presign { .. } => {
+                Err("Presigned URLs are not supported by the filesystem backend".into())
+            }
+
+            StorageOp::CreateMultipartUpload { key, options } => {
+                resolve(root, &key)?;
+                check_multipart_options(&options)?;
+
+                let upload_id = new_upload_id();
+                let upload = PendingUpload {
+                    key: key.clone(),
+                    options,
+                };
+                let json = serde_json::to_vec(&upload).map_err(|e| e.to_string())?;
+
+                write_file(
+                    root,
+                    &upload_dir(root, &upload_id)?.join("upload.json"),
+                    &json,
+                )?;
+
+                Ok(StorageResult::MultipartUpload { key, upload_id })
+            }
+
+            StorageOp::UploadPart {
+                key,
+                upload_id,
+                part_number,
+                body,
+            } => {
+                check_part_number(part_number)?;
+                pending_upload(root, &key, &upload_id)?;
+
+                let part = upload_dir(root, &upload_id)?.join(part_number.to_string());
+                write_file(root, &part, &body)?;
+
+                Ok(StorageResult::UploadedPart(UploadedPart {
+                    part_number,
+                    etag: etag(&body),
+                }))
+            }
+
+            StorageOp::CompleteMultipartUpload {
+                key,
+                upload_id,
+                parts,
+            } => {
+                check_completed_parts(&parts)?;
+
+                let upload = pending_upload(root, &key, &upload_id)?;
+                let dir = upload_dir(root, &upload_id)?;
+                let path = resolve(root, &key)?;
+                let (tmp, mut file) = create_tmp(root, &path)?;
+
+                for (index, part) in parts.iter().enumerate() {
+                    let chunk = read_file(root, &dir.join(part.part_number.to_string()))?;
+
+                    let Some(chunk) = chunk.filter(|chunk| etag(chunk) == part.etag) else {
+                        return Err(format!("Invalid part {}", part.part_number));
+                    };
+
+                    check_part_size(
+                        part.part_number,
+                        chunk.len() as u64,
+                        index + 1 == parts.len(),
+                    )?;
+                    file.write_all(&chunk).map_err(|e| e.to_string())?;
+                }
+
+                file.sync_all()
+                    .and_then(|_| fs::rename(tmp.path(), &path))
+                    .map_err(|e| e.to_string())?;
+                tmp.persist();
+
+                let info = file_info(root, &path)?.ok_or("Object removed while completing")?;
+                let sidecar = Sidecar {
+                    options: upload.options,
+                    multipart: Some(MultipartEtag {
+                        etag: multipart_etag(&parts),
+                        file_etag: info.etag(),
+                    }),
+                };
+
+                write_meta(root, &key, &sidecar)?;
+                fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::metadata(object_metadata(root, &key)?))
+            }
+
+            StorageOp::AbortMultipartUpload { key, upload_id } => {
+                pending_upload(root, &key, &upload_id)?;
+
+                fs::remove_dir_all(upload_dir(root, &upload_id)?).map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::Body(None))
+            }
+        }
+    }
+
+    fn file_response(
+        &self,
+        root: &Path,
+        key: &str,
+        head: bool,
+        options: &GetOptions,
+    ) -> Result<HttpResponse, String> {
+        let path = root.join(key);
+
+        let Some(mut metadata) = object_metadata(root, key)? else {
+            return Ok(not_found());
+        };
+
+        if metadata.http_metadata.content_type.is_none() {
+            metadata.http_metadata.content_type = Some(content_type(&path).into());
+        }
+
+        let range = match options.evaluate(&metadata) {
+            Ok(range) => range,
+            Err(rejection) => return Ok(rejection_response(&metadata, rejection)),
+        };
+
+        let body = if head {
+            ResponseBody::None
+        } else {
+            match read_range(root, &path, range)? {
+                Some(body) => ResponseBody::Bytes(Bytes::from(body)),
+                None => return Ok(not_found()),
+            }
+        };
+
+        Ok(object_response(&metadata, range, body))
+    }
+}
+
+impl OperationsHandler for FsBackend {
+    fn handle_binding_fetch(
+        &self,
+        binding: &str,
+        request: HttpRequest,
+    ) -> OpFuture<'_, Result<HttpResponse, String>> {
+        let result = self.fetch_asset(binding, &request);
+        Box::pin(async move { result })
+    }
+
+    fn handle_binding_storage(&self, binding: &str, op: StorageOp) -> OpFuture<'_, StorageResult> {
+        let binding = binding.to_string();
+        Box::pin(async move { self.execute_storage(&binding, op).await })
+    }
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse {
+        status: 404,
+        headers: vec![],
+        body: ResponseBody::None,
+    }
+}
+
+/// Resolve an object key to a path inside `root`
+///
+/// Only checks the key syntactically; symlinks are checked when the file
+/// is accessed (see [`check_inside`]).
+fn resolve(root: &Path, key: &str) -> Result<PathBuf, String> {
+    let invalid = || format!("Invalid key: '{}'", key);
+
+    if key.is_empty() || key.contains('\\') || key.contains('\0') {
+        return Err(invalid());
+    }
+
+    let relative = Path::new(key);
+
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) if !name.to_string_lossy().starts_with(RESERVED_PREFIX) => {}
+            Component::CurDir => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(root.join(relative))
+}
+
+/// Ensure an existing path does not escape `root` through symlinks
+fn check_inside(root: &Path, path: &Path) -> Result<(), String> {
+    let root = root.canonicalize().map_err(|e| e.to_string())?;
+    let path = path.canonicalize().map_err(|e| e.to_string())?;
+
+    if path.starts_with(&root) {
+        Ok(())
+    } else {
+        Err("Path escapes binding directory".into())
+    }
+}
+
+/// Metadata of a regular file, `None` if missing
+fn file_info(root: &Path, path: &Path) -> Result<Option<FileInfo>, String> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => return Ok(None),
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    check_inside(root, path)?;
+
+    Ok(Some(FileInfo {
+        size: metadata.len(),
+        modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+    }))
+}
+
+/// Metadata sidecar path of an object key
+fn meta_path(root: &Path, key: &str) -> PathBuf {
+    root.join(META_DIR).join(format!("{}.json", key))
+}
+
+/// Metadata of an object (file info + sidecar), `None` if missing
+fn object_metadata(root: &Path, key: &str) -> Result<Option<ObjectMetadata>, String> {
+    let Some(info) = file_info(root, &root.join(key))? else {
+        return Ok(None);
+    };
+
+    Ok(Some(info.into_metadata(key, read_meta(root, key)?)))
+}
+
+/// Object metadata stored in its sidecar (default if there is none)
+fn read_meta(root: &Path, key: &str) -> Result<Sidecar, String> {
+    match fs::read(meta_path(root, key)) {
+        Ok(json) => serde_json::from_slice(&json).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Sidecar::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Store object metadata in its sidecar (removed when empty)
+fn write_meta(root: &Path, key: &str, sidecar: &Sidecar) -> Result<(), String> {
+    let path = meta_path(root, key);
+
+    if *sidecar == Sidecar::default() {
+        match fs::remove_file(&path) {
+            Ok(()) => remove_empty_parents(&root.join(META_DIR), &path),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+
+        return Ok(());
+    }
+
+    let json = serde_json::to_vec(sidecar).map_err(|e| e.to_string())?;
+    write_file(root, &path, &json)
+}
+
+/// Directory of a multipart upload (upload ids are alphanumeric)
+fn upload_dir(root: &Path, upload_id: &str) -> Result<PathBuf, String> {
+    if upload_id.is_empty() || !upload_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Multipart upload '{}' not found", upload_id));
+    }
+
+    Ok(root.join(UPLOADS_DIR).join(upload_id))
+}
+
+/// Pending multipart upload for `key`
+fn pending_upload(root: &Path, key: &str, upload_id: &str) -> Result<PendingUpload, String> {
+    let not_found = || format!("Multipart upload '{}' not found", upload_id);
+    let path = upload_dir(root, upload_id)?.join("upload.json");
+
+    let json = read_file(root, &path)?.ok_or_else(not_found)?;
+    let upload: PendingUpload = serde_json::from_slice(&json).map_err(|e| e.to_string())?;
+
+    if upload.key != key {
+        return Err(not_found());
+    }
+
+    Ok(upload)
+}
+
+/// Contents of a regular file, `None` if missing
+fn read_file(root: &Path, path: &Path) -> Result<Option<Vec<u8>>, String> {
+    if file_info(root, path)?.is_none() {
+        return Ok(None);
+    }
+
+    fs::read(path).map(Some).map_err(|e| e.to_string())
+}
+
+/// Contents of a byte range of a regular file (whole file if None), `None` if missing
+fn read_range(
+    root: &Path,
+    path: &Path,
+    range: Option<ContentRange>,
+) -> Result<Option<Vec<u8>>, String> {
+    let Some(range) = range else {
+        return read_file(root, path);
+    };
+
+    let Some(file) = open_range(root, path, Some(range))? else {
+        return Ok(None);
+    };
+
+    let mut body = Vec::with_capacity(range.length as usize);
+    file.take(range.length)
+        .read_to_end(&mut body)
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(body))
+}
+
+/// Open a regular file positioned at the start of `range`, `None` if missing
+fn open_range(
+    root: &Path,
+    path: &Path,
+    range: Option<ContentRange>,
+) -> Result<Option<fs::File>, String> {
+    if file_info(root, path)?.is_none() {
+        return Ok(None);
+    }
+
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+
+    if let Some(range) = range {
+        file.seek(SeekFrom::Start(range.offset))
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(Some(file))
+}
+
+/// Stream a byte range of a regular file (whole file if None) in chunks
+/// from the blocking thread pool, `None` if missing
+fn stream_file(
+    root: &Path,
+    path: &Path,
+    range: Option<ContentRange>,
+) -> Result<Option<StorageStream>, String> {
+    let Some(info) = file_info(root, path)? else {
+        return Ok(None);
+    };
+
+    let Some(file) = open_range(root, path, range)? else {
+        return Ok(None);
+    };
+
+    let length = range.map_or(info.size, |range| range.length);
+    let mut file = file.take(length);
+    let (tx, stream) = StorageStream::channel(Some(length), STREAM_BUFFER_SIZE);
+
+    tokio::task::spawn_blocking(move || {
+        let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    // Receiver dropped: stop reading
+                    if tx
+                        .blocking_send(Ok(Bytes::copy_from_slice(&buf[..n])))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(e.to_string()));
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(Some(stream))
+}
+
+/// Run blocking file system calls on the blocking thread pool
+async fn blocking<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Temporary file, removed on drop unless persisted (renamed over its target)
+struct TmpFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl TmpFile {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the file: it has been renamed over its target
+    fn persist(mut self) {
+        self.persisted = true;
+    }
+}
+
+impl Drop for TmpFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Create a temporary file next to `path`, to be renamed over it once written
+fn create_tmp(root: &Path, path: &Path) -> Result<(TmpFile, fs::File), String> {
+    let parent = path.parent().unwrap_or(root);
+
+    fs::create_dir_all(root).map_err(|e| e.to_string())?;
+
+    // A subdirectory may be a symlink pointing outside: check the nearest
+    // existing ancestor before creating anything below it
+    let existing = parent
+        .ancestors()
+        .find(|dir| fs::symlink_metadata(dir).is_ok())
+        .unwrap_or(root);
+    check_inside(root, existing)?;
+
+    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    check_inside(root, parent)?;
+
+    if path.is_dir() {
+        return Err("Key refers to a directory".into());
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let tmp = parent.join(format!("{}{:x}-{}", TMP_PREFIX, nonce, file_name));
+
+    let file = fs::File::create(&tmp).map_err(|e| e.to_string())?;
+
+    Ok((
+        TmpFile {
+            path: tmp,
+            persisted: false,
+        },
+        file,
+    ))
+}
+
+/// Write a file atomically (temporary file + rename)
+fn write_file(root: &Path, path: &Path, body: &[u8]) -> Result<(), String> {
+    let (tmp, mut file) = create_tmp(root, path)?;
+
+    file.write_all(body)
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(tmp.path(), path))
+        .map_err(|e| e.to_string())?;
+    tmp.persist();
+
+    Ok(())
+}
+
+/// Copy a file atomically (temporary file + rename)
+fn copy_file(root: &Path, source: &Path, target: &Path) -> Result<(), String> {
+    let mut input = fs::File::open(source).map_err(|e| e.to_string())?;
+    let (tmp, mut file) = create_tmp(root, target)?;
+
+    std::io::copy(&mut input, &mut file)
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(tmp.path(), target))
+        .map_err(|e| e.to_string())?;
+    tmp.persist();
+
+    Ok(())
+}
+
+/// Remove empty directories between `path` and `root` (exclusive)
+fn remove_empty_parents(root: &Path, path: &Path) {
+    let mut dir = path.parent();
+
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) {
+            break;
+        }
+
+        // Fails (and stops) as soon as a directory is not empty
+        if fs::remove_dir(current).is_err() {
+            break;
+        }
+
+        dir = current.parent();
+    }
+}
+
+/// Recursively collect file keys under `dir`
+fn collect_keys(root: &Path, dir: &Path, keys: &mut Vec<String>) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if name.starts_with(RESERVED_PREFIX) {
+            continue;
+        }
+
+        // Symlinks are not followed when listing
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            collect_keys(root, &path, keys)?;
+        } else if file_type.is_file()
+            && let Some(key) = key_of(root, &path)
+        {
+            keys.push(key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Object key of a path inside `root` (components joined with `/`)
+fn key_of(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+
+    let key = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    Some(key)
+}
+
+/// Decoded path of a URL without the leading slash, query or fragment
+fn url_path(url: &str) -> Option<String> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |idx| &rest[idx..]),
+        None => url,
+    };
+
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    percent_decode(path.trim_start_matches('/'))
+}
+
+/// Decode `%XX` escapes, `None` if malformed or not UTF-8
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Guess a content type from the file extension
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory under the system temp dir, removed on drop
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let nonce = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            let path = std::env::temp_dir().join(format!("ow-fs-{}-{:x}", name, nonce));
+
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn put(key: &str, body: &[u8]) -> StorageOp {
+        StorageOp::Put {
+            key: key.into(),
+            body: body.to_vec(),
+            options: PutOptions::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn put_creates_root_and_parents() {
+        let dir = TempDir::new("put");
+        let backend = FsBackend::new().with_binding("BUCKET", dir.0.join("root"));
+
+        let result = backend
+            .execute_storage("BUCKET", put("a/b/c.txt", b"hello"))
+            .await;
+        assert!(matches!(result, StorageResult::Metadata(ref meta) if meta.size == 5));
+
+        let result = backend
+            .execute_storage(
+                "BUCKET",
+                StorageOp::Get {
+                    key: "a/b/c.txt".into(),
+                    options: GetOptions::default(),
+                },
+            )
+            .await;
+        assert!(matches!(result, StorageResult::Object(ref object) if object.body == b"hello"));
+    }
+
+    #[tokio::test]
+    async fn streamed_put_and_get() {
+        let dir = TempDir::new("stream");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        let (tx, body) = StorageStream::channel(Some(10), 4);
+        tx.send(Ok(Bytes::from_static(b"hello "))).await.unwrap();
+        tx.send(Ok(Bytes::from_static(b"fs!!"))).await.unwrap();
+        drop(tx);
+
+        let op = StorageOp::PutStream {
+            key: "stream.txt".into(),
+            body,
+            options: PutOptions::default(),
+        };
+        let result = backend.execute_storage("BUCKET", op).await;
+        assert!(matches!(result, StorageResult::Metadata(ref meta) if meta.size == 10));
+
+        let op = StorageOp::GetStream {
+            key: "stream.txt".into(),
+            options: GetOptions::default(),
+        };
+        let StorageResult::Stream(object) = backend.execute_storage("BUCKET", op).await else {
+            panic!("expected a stream");
+        };
+        assert_eq!(object.body.collect().await.unwrap(), b"hello fs!!");
+    }
+
+    #[tokio::test]
+    async fn dropped_put_stream_removes_tmp_file() {
+        let dir = TempDir::new("cancel");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        let (tx, body) = StorageStream::channel(None, 4);
+        tx.send(Ok(Bytes::from_static(b"partial"))).await.unwrap();
+
+        let op = StorageOp::PutStream {
+            key: "file.txt".into(),
+            body,
+            options: PutOptions::default(),
+        };
+
+        // The sender stays open: the put waits for more chunks until dropped
+        let put = backend.execute_storage("BUCKET", op);
+        let timeout = tokio::time::timeout(std::time::Duration::from_millis(50), put);
+        assert!(timeout.await.is_err());
+
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch_fails_put() {
+        let dir = TempDir::new("checksum");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        let options = PutOptions {
+            checksums: crate::Checksums {
+                sha256: Some("00".repeat(32)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let op = StorageOp::Put {
+            key: "file.txt".into(),
+            body: b"hello".to_vec(),
+            options: options.clone(),
+        };
+        let result = backend.execute_storage("BUCKET", op).await;
+        assert!(matches!(result, StorageResult::Error(ref err) if err.contains("SHA-256")));
+
+        let op = StorageOp::PutStream {
+            key: "file.txt".into(),
+            body: StorageStream::from_bytes(Bytes::from_static(b"hello")),
+            options,
+        };
+        let result = backend.execute_storage("BUCKET", op).await;
+        assert!(matches!(result, StorageResult::Error(ref err) if err.contains("SHA-256")));
+
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 0);
+    }
+
+    fn request(method: HttpMethod, url: &str) -> HttpRequest {
+        HttpRequest {
+            method,
+            url: url.into(),
+            headers: HashMap::new(),
+            body: crate::RequestBody::None,
+        }
+    }
+
+    fn header<'a>(response: &'a HttpResponse, name: &str) -> Option<&'a str> {
+        response
+            .headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn resolve_rejects_unsafe_keys() {
+        let root = Path::new("/srv/bucket");
+
+        for key in [
+            "",
+            "..",
+            "../secret",
+            "a/../../b",
+            "/etc/passwd",
+            "a\\..\\b",
+            "a\0b",
+            ".ow-meta/a",
+            "a/.ow-uploads/b",
+        ] {
+            assert!(resolve(root, key).is_err(), "{:?} should be rejected", key);
+        }
+
+        assert_eq!(resolve(root, "a/./b.txt").unwrap(), root.join("a/./b.txt"));
+    }
+
+    #[test]
+    fn fetch_rejects_traversal() {
+        let dir = TempDir::new("traversal");
+        let root = dir.0.join("root");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(dir.0.join("secret.txt"), b"secret").unwrap();
+
+        let backend = FsBackend::new().with_binding("ASSETS", &root);
+
+        for url in [
+            "https://example.com/../secret.txt",
+            "https://example.com/%2e%2e/secret.txt",
+            "https://example.com/%2E%2E%2Fsecret.txt",
+            "https://example.com//etc/passwd",
+            "https://example.com/a%5C..%5Csecret.txt",
+            "https://example.com/a%00b",
+            "https://example.com/.ow-meta/index.html",
+            "https://example.com/%zz",
+        ] {
+            let response = backend
+                .fetch_asset("ASSETS", &request(HttpMethod::Get, url))
+                .unwrap();
+            assert_eq!(response.status, 404, "{}", url);
+        }
+    }
+
+    #[test]
+    fn fetch_refuses_dotfiles() {
+        let dir = TempDir::new("dotfiles");
+        fs::write(dir.0.join(".env"), b"SECRET=1").unwrap();
+        fs::create_dir_all(dir.0.join(".git")).unwrap();
+        fs::write(dir.0.join(".git/config"), b"[core]").unwrap();
+
+        let backend = FsBackend::new().with_binding("ASSETS", &dir.0);
+
+        for url in ["/.env", "/.git/config", "/%2eenv", "/./.env"] {
+            let response = backend
+                .fetch_asset("ASSETS", &request(HttpMethod::Get, url))
+                .unwrap();
+            assert_eq!(response.status, 404, "{}", url);
+        }
+    }
+
+    #[test]
+    fn fetch_serves_files_with_headers() {
+        let dir = TempDir::new("assets");
+        fs::create_dir_all(dir.0.join("docs")).unwrap();
+        fs::write(dir.0.join("index.html"), b"<h1>home</h1>").unwrap();
+        fs::write(dir.0.join("docs/index.html"), b"<h1>docs</h1>").unwrap();
+        fs::write(dir.0.join("style.css"), b"body {}").unwrap();
+
+        let backend = FsBackend::new().with_binding("ASSETS", &dir.0);
+        let fetch = |method, url| {
+            backend
+                .fetch_asset("ASSETS", &request(method, url))
+                .unwrap()
+        };
+
+        let response = fetch(HttpMethod::Get, "https://example.com/style.css?v=1");
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            header(&response, "content-type"),
+            Some("text/css; charset=utf-8")
+        );
+        assert_eq!(header(&response, "content-length"), Some("7"));
+        assert!(header(&response, "etag").is_some_and(|etag| etag.starts_with('"')));
+        let modified = header(&response, "last-modified").unwrap();
+        assert!(httpdate::parse_http_date(modified).is_ok());
+        assert!(matches!(response.body, ResponseBody::Bytes(ref body) if body == "body {}"));
+
+        for (url, body) in [
+            ("https://example.com/", "<h1>home</h1>"),
+            ("https://example.com/docs/", "<h1>docs</h1>"),
+            ("https://example.com/docs", "<h1>docs</h1>"),
+        ] {
+            let response = fetch(HttpMethod::Get, url);
+            assert_eq!(response.status, 200, "{}", url);
+            assert_eq!(
+                header(&response, "content-type"),
+                Some("text/html; charset=utf-8")
+            );
+            assert!(matches!(response.body, ResponseBody::Bytes(ref bytes) if bytes == body));
+        }
+
+        let response = fetch(HttpMethod::Head, "https://example.com/style.css");
+        assert_eq!(response.status, 200);
+        assert_eq!(header(&response, "content-length"), Some("7"));
+        assert!(matches!(response.body, ResponseBody::None));
+
+        let response = fetch(HttpMethod::Get, "https://example.com/missing.css");
+        assert_eq!(response.status, 404);
+
+        let response = fetch(HttpMethod::Post, "https://example.com/style.css");
+        assert_eq!(response.status, 405);
+        assert_eq!(header(&response, "allow"), Some("GET, HEAD"));
+    }
+
+    #[tokio::test]
+    async fn storage_objects() {
+        let dir = TempDir::new("objects");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        crate::backends::tests::storage_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn list_objects() {
+        let dir = TempDir::new("list");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        crate::backends::tests::list_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn copy_and_delete_objects() {
+        let dir = TempDir::new("copy");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        crate::backends::tests::copy_delete_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn multipart_upload() {
+        let dir = TempDir::new("multipart");
+        let backend = FsBackend::new().with_binding("BUCKET", &dir.0);
+
+        crate::backends::tests::multipart_scenario(&backend).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn put_through_symlink_creates_nothing_outside() {
+        let dir = TempDir::new("symlink");
+        let (root, outside) = (dir.0.join("root"), dir.0.join("outside"));
+
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        let backend = FsBackend::new().with_binding("BUCKET", &root);
+        let result = backend
+            .execute_storage("BUCKET", put("link/sub/file.txt", b"x"))
+            .await;
+
+        assert!(matches!(result, StorageResult::Error(_)));
+        assert!(!outside.join("sub").exists());
+    }
+}
@@ -0,0 +1,394 @@
+//! In-memory HTTP cache
+//!
+//! Reference implementation of `CacheOp` semantics:
+//! - Entries are keyed by cache name and request URL (without fragment)
+//! - Only `GET` requests match, unless `ignore_method` is set
+//! - Responses are stored for `s-maxage`, else `max-age` seconds, else
+//!   until `Expires`; responses with `no-store`, `no-cache`, `private`,
+//!   `Set-Cookie`, a status not cacheable by default or no freshness
+//!   lifetime are not stored (see `CachePolicy`)
+//! - Stale responses (`max-age=0`) are never matched: storing one only
+//!   replaces the previous response
+//! - A response with `Vary` is stored per value of the request headers it
+//!   names, and only matches requests with the same values
+//! - Matched responses carry an `Age` header
+//! - `Delete` removes every variant of the URL
+//! - The total size of entries (URL, headers and body) is bounded: the least
+//!   recently used URLs are evicted first, and responses larger than the
+//!   whole cache are not stored
+
+use crate::{
+    CacheOp, CachePolicy, CacheQueryOptions, CacheResult, HttpRequest, HttpResponse, OpFuture,
+    OperationsHandler, ResponseBody,
+};
+use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default capacity in bytes (64 MiB)
+const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Longest storage lifetime in seconds (1 year)
+const MAX_TTL: u64 = 365 * 24 * 60 * 60;
+
+/// Cache name (None for the default cache) and URL
+type Key = (Option<String>, String);
+
+#[derive(Debug)]
+struct Variant {
+    /// Request header values the response varies on
+    vary: Vec<(String, Option<String>)>,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Option<Bytes>,
+    stored_at: Instant,
+    expires_at: Instant,
+    size: usize,
+}
+
+impl Variant {
+    fn matches(&self, request: &HttpRequest) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| request_header(request, name) == value.as_deref())
+    }
+
+    fn response(&self, now: Instant) -> HttpResponse {
+        let mut headers: Vec<_> = self
+            .headers
+            .iter()
+            .filter(|(name, _)| !name.eq_ignore_ascii_case("age"))
+            .cloned()
+            .collect();
+        let age = now.duration_since(self.stored_at).as_secs();
+        headers.push(("age".to_string(), age.to_string()));
+
+        HttpResponse {
+            status: self.status,
+            headers,
+            body: match &self.body {
+                Some(body) => ResponseBody::Bytes(body.clone()),
+                None => ResponseBody::None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    variants: Vec<Variant>,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<Key, Entry>,
+    /// Keys by last use, least recent first
+    recency: BTreeMap<u64, Key>,
+    tick: u64,
+    size: usize,
+}
+
+impl Lru {
+    /// Mark an entry as most recently used
+    fn touch(&mut self, key: &Key) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+
+        self.recency.remove(&entry.last_used);
+        self.tick += 1;
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, key.clone());
+    }
+
+    fn remove(&mut self, key: &Key) -> bool {
+        let Some(entry) = self.entries.remove(key) else {
+            return false;
+        };
+
+        self.recency.remove(&entry.last_used);
+        self.size -= entry.variants.iter().map(|v| v.size).sum::<usize>();
+        true
+    }
+
+    /// Drop the expired variants of an entry (and the entry if none is left)
+    fn purge(&mut self, key: &Key, now: Instant) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+
+        let before: usize = entry.variants.iter().map(|v| v.size).sum();
+        entry.variants.retain(|v| v.expires_at > now);
+        let after: usize = entry.variants.iter().map(|v| v.size).sum();
+        self.size -= before - after;
+
+        if entry.variants.is_empty() {
+            self.remove(key);
+        }
+    }
+}
+
+/// In-memory cache implementing `handle_cache`, bounded by bytes
+///
+/// Intended for tests and local development. All entries are lost on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// let cache = MemoryCache::with_capacity(16 * 1024 * 1024);
+/// let ops: OperationsHandle = Arc::new(cache);
+/// ```
+#[derive(Debug)]
+pub struct MemoryCache {
+    max_bytes: usize,
+    state: Mutex<Lru>,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_MAX_BYTES)
+    }
+}
+
+impl MemoryCache {
+    /// Create an empty cache holding up to 64 MiB
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty cache holding up to `max_bytes`
+    pub fn with_capacity(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Total size of the stored entries in bytes
+    pub fn size(&self) -> usize {
+        self.state.lock().unwrap().size
+    }
+
+    /// Execute a cache operation against a cache (None for the default cache)
+    pub async fn execute(&self, cache: Option<&str>, op: CacheOp) -> CacheResult {
+        match op {
+            CacheOp::Match { request, options } => {
+                CacheResult::Response(self.lookup(cache, &request, &options))
+            }
+
+            CacheOp::Put { request, response } => {
+                let policy = match CachePolicy::for_response(&request, &response) {
+                    Ok(policy) => policy,
+                    Err(err) => return CacheResult::Error(err),
+                };
+
+                let Some(ttl) = policy.ttl else {
+                    return CacheResult::Ok;
+                };
+
+                let HttpResponse {
+                    status,
+                    headers,
+                    body,
+                } = response;
+
+                let body = body.collect().await;
+                let url = cache_key(&request).to_string();
+
+                let size = url.len()
+                    + body.as_ref().map_or(0, |body| body.len())
+                    + headers
+                        .iter()
+                        .map(|(name, value)| name.len() + value.len())
+                        .sum::<usize>();
+
+                if size > self.max_bytes {
+                    return CacheResult::Ok;
+                }
+
+                let now = Instant::now();
+                let vary = policy
+                    .vary
+                    .into_iter()
+                    .map(|name| {
+                        let value = request_header(&request, &name).map(str::to_string);
+                        (name, value)
+                    })
+                    .collect();
+
+                let variant = Variant {
+                    vary,
+                    status,
+                    headers,
+                    body,
+                    stored_at: now,
+                    expires_at: now + Duration::from_secs(ttl.min(MAX_TTL)),
+                    size,
+                };
+
+                self.store((cache.map(str::to_string), url), variant, now);
+
+                CacheResult::Ok
+            }
+
+            CacheOp::Delete { request, options } => {
+                if !options.allows(&request) {
+                    return CacheResult::Deleted(false);
+                }
+
+                let key = (cache.map(str::to_string), cache_key(&request).to_string());
+                CacheResult::Deleted(self.state.lock().unwrap().remove(&key))
+            }
+        }
+    }
+
+    fn lookup(
+        &self,
+        cache: Option<&str>,
+        request: &HttpRequest,
+        options: &CacheQueryOptions,
+    ) -> Option<HttpResponse> {
+        if !options.allows(request) {
+            return None;
+        }
+
+        let now = Instant::now();
+        let key = (cache.map(str::to_string), cache_key(request).to_string());
+        let mut lru = self.state.lock().unwrap();
+
+        lru.purge(&key, now);
+
+        let response = lru
+            .entries
+            .get(&key)?
+            .variants
+            .iter()
+            .find(|variant| variant.matches(request))?
+            .response(now);
+
+        lru.touch(&key);
+
+        Some(response)
+    }
+
+    fn store(&self, key: Key, variant: Variant, now: Instant) {
+        let mut guard = self.state.lock().unwrap();
+        let lru = &mut *guard;
+
+        lru.purge(&key, now);
+
+        let entry = lru.entries.entry(key.clone()).or_default();
+
+        // Replace the variant for the same request header values
+        if let Some(i) = entry.variants.iter().position(|v| v.vary == variant.vary) {
+            lru.size -= entry.variants.remove(i).size;
+        }
+
+        // Stale on arrival: only replaces the previous variant
+        if variant.expires_at <= now {
+            if entry.variants.is_empty() {
+                lru.remove(&key);
+            }
+
+            return;
+        }
+
+        lru.size += variant.size;
+        entry.variants.push(variant);
+        lru.touch(&key);
+
+        // The entry just stored is the most recent: evicted last
+        while lru.size > self.max_bytes {
+            let Some((_, oldest)) = lru.recency.first_key_value() else {
+                break;
+            };
+
+            let oldest = oldest.clone();
+            lru.remove(&oldest);
+        }
+    }
+}
+
+/// Cache key of a request: its URL without fragment
+fn cache_key(request: &HttpRequest) -> &str {
+    match request.url.split_once('#') {
+        Some((url, _)) => url,
+        None => &request.url,
+    }
+}
+
+/// Value of a request header, by case-insensitive name
+fn request_header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+impl OperationsHandler for MemoryCache {
+    fn handle_cache(&self, cache: Option<&str>, op: CacheOp) -> OpFuture<'_, CacheResult> {
+        let cache = cache.map(str::to_string);
+        Box::pin(async move { self.execute(cache.as_deref(), op).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HttpMethod, RequestBody};
+
+    fn request() -> HttpRequest {
+        HttpRequest {
+            method: HttpMethod::Get,
+            url: "https://example.com/a".into(),
+            headers: Default::default(),
+            body: RequestBody::None,
+        }
+    }
+
+    async fn put(cache: &MemoryCache, cache_control: &str) {
+        let response = HttpResponse {
+            status: 200,
+            headers: vec![("cache-control".into(), cache_control.into())],
+            body: ResponseBody::Bytes(Bytes::from_static(b"hello")),
+        };
+
+        let result = cache
+            .execute(
+                None,
+                CacheOp::Put {
+                    request: request(),
+                    response,
+                },
+            )
+            .await;
+        assert!(matches!(result, CacheResult::Ok));
+    }
+
+    async fn matches(cache: &MemoryCache) -> bool {
+        let op = CacheOp::Match {
+            request: request(),
+            options: CacheQueryOptions::default(),
+        };
+
+        matches!(
+            cache.execute(None, op).await,
+            CacheResult::Response(Some(_))
+        )
+    }
+
+    #[tokio::test]
+    async fn stale_response_replaces_fresh_one() {
+        let cache = MemoryCache::new();
+
+        put(&cache, "max-age=60").await;
+        assert!(matches(&cache).await);
+
+        put(&cache, "max-age=0").await;
+        assert!(!matches(&cache).await);
+        assert_eq!(cache.size(), 0);
+    }
+}
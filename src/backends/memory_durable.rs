@@ -0,0 +1,320 @@
+//! In-memory durable object storage
+//!
+//! Reference implementation of `DurableStorageOp` semantics:
+//! - Keys are non-empty UTF-8 strings of at most `max_key_size` bytes
+//! - Values are JSON, at most `max_value_size` bytes once serialized
+//! - Get, put and delete accept at most `max_keys` keys; every operation
+//!   is validated before anything is written
+//! - `Get` and `List` return entries in lexicographic (byte) order
+//! - `Transaction` applies its operations in place and undoes them all if
+//!   one fails
+//! - One alarm at most; `DeleteAll` also cancels it
+
+use crate::{
+    DurableStorageLimits, DurableStorageOp, DurableStorageResult, OpFuture, OperationsHandler,
+};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+struct State {
+    entries: BTreeMap<String, serde_json::Value>,
+    alarm: Option<u64>,
+}
+
+/// Previous state of what an operation changed, to undo it
+enum Undo {
+    /// Previous value of a key (`None` if it was absent)
+    Entry(String, Option<serde_json::Value>),
+    /// All entries before `DeleteAll`
+    Entries(BTreeMap<String, serde_json::Value>),
+    Alarm(Option<u64>),
+}
+
+impl State {
+    /// Undo changes, most recent first
+    fn undo(&mut self, changes: impl DoubleEndedIterator<Item = Undo>) {
+        for change in changes.rev() {
+            match change {
+                Undo::Entry(key, Some(value)) => {
+                    self.entries.insert(key, value);
+                }
+                Undo::Entry(key, None) => {
+                    self.entries.remove(&key);
+                }
+                Undo::Entries(entries) => self.entries = entries,
+                Undo::Alarm(alarm) => self.alarm = alarm,
+            }
+        }
+    }
+}
+
+/// Storage of a single durable object implementing `handle_durable_storage`
+///
+/// A runner creates one per hosted object and includes it in the operations
+/// handle of the worker hosting that object. Intended for tests and local
+/// development. All data is lost on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// let storage = Arc::new(MemoryDurableStorage::new());
+/// let ops: OperationsHandle = storage.clone();
+/// // Deliver the alarm once it is due, then clear it
+/// if let Some(time) = storage.alarm() {
+///     let (event, rx) = Event::alarm(time, 0);
+///     worker.exec(event).await?;
+///     if rx.await?.success {
+///         storage.complete_alarm(time);
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryDurableStorage {
+    limits: DurableStorageLimits,
+    state: Mutex<State>,
+}
+
+impl MemoryDurableStorage {
+    /// Create an empty storage with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty storage with custom limits
+    pub fn with_limits(limits: DurableStorageLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Limits enforced by this storage
+    pub fn limits(&self) -> &DurableStorageLimits {
+        &self.limits
+    }
+
+    /// Execute a storage operation
+    pub fn execute(&self, op: DurableStorageOp) -> DurableStorageResult {
+        let mut state = self.state.lock().unwrap();
+
+        match self.apply(&mut state, op, &mut Vec::new()) {
+            Ok(result) => result,
+            Err(err) => DurableStorageResult::Error(err),
+        }
+    }
+
+    /// Scheduled alarm time (Unix timestamp in milliseconds), if any
+    pub fn alarm(&self) -> Option<u64> {
+        self.state.lock().unwrap().alarm
+    }
+
+    /// Clear the alarm after a successful delivery
+    ///
+    /// Keeps it if the handler scheduled another time in the meantime.
+    pub fn complete_alarm(&self, scheduled_time: u64) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.alarm == Some(scheduled_time) {
+            state.alarm = None;
+        }
+    }
+
+    /// Apply an operation, recording in `undo` how to revert its changes
+    fn apply(
+        &self,
+        state: &mut State,
+        op: DurableStorageOp,
+        undo: &mut Vec<Undo>,
+    ) -> Result<DurableStorageResult, String> {
+        match op {
+            DurableStorageOp::Get { mut keys } => {
+                self.check_keys(&keys)?;
+
+                keys.sort();
+                keys.dedup();
+
+                let entries = keys
+                    .into_iter()
+                    .filter_map(|key| {
+                        let value = state.entries.get(&key)?.clone();
+                        Some((key, value))
+                    })
+                    .collect();
+
+                Ok(DurableStorageResult::Entries(entries))
+            }
+
+            DurableStorageOp::Put { entries } => {
+                self.limits.check_keys(entries.len())?;
+
+                for (key, value) in &entries {
+                    self.limits.check_key(key)?;
+                    self.limits.check_value(value)?;
+                }
+
+                for (key, value) in entries {
+                    let previous = state.entries.insert(key.clone(), value);
+                    undo.push(Undo::Entry(key, previous));
+                }
+
+                Ok(DurableStorageResult::Ok)
+            }
+
+            DurableStorageOp::Delete { keys } => {
+                self.check_keys(&keys)?;
+
+                let mut deleted = 0;
+
+                for key in keys {
+                    if let Some(previous) = state.entries.remove(&key) {
+                        undo.push(Undo::Entry(key, Some(previous)));
+                        deleted += 1;
+                    }
+                }
+
+                Ok(DurableStorageResult::Deleted(deleted as u64))
+            }
+
+            DurableStorageOp::DeleteAll => {
+                undo.push(Undo::Entries(std::mem::take(&mut state.entries)));
+                undo.push(Undo::Alarm(state.alarm.take()));
+                Ok(DurableStorageResult::Ok)
+            }
+
+            DurableStorageOp::List { options } => {
+                let limit = options.limit.map_or(usize::MAX, |limit| limit as usize);
+                let matching = state.entries.iter().filter(|(key, _)| options.matches(key));
+
+                let entries: Vec<_> = if options.reverse {
+                    matching
+                        .rev()
+                        .take(limit)
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect()
+                } else {
+                    matching
+                        .take(limit)
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect()
+                };
+
+                Ok(DurableStorageResult::Entries(entries))
+            }
+
+            DurableStorageOp::Transaction { ops } => {
+                let start = undo.len();
+                let mut results = Vec::with_capacity(ops.len());
+
+                for (i, op) in ops.into_iter().enumerate() {
+                    match self.apply(state, op, undo) {
+                        Ok(result) => results.push(result),
+                        Err(err) => {
+                            state.undo(undo.drain(start..));
+                            return Err(format!("Operation {}: {}", i, err));
+                        }
+                    }
+                }
+
+                Ok(DurableStorageResult::Transaction(results))
+            }
+
+            DurableStorageOp::GetAlarm => Ok(DurableStorageResult::Alarm(state.alarm)),
+
+            DurableStorageOp::SetAlarm { scheduled_time } => {
+                undo.push(Undo::Alarm(state.alarm.replace(scheduled_time)));
+                Ok(DurableStorageResult::Ok)
+            }
+
+            DurableStorageOp::DeleteAlarm => {
+                undo.push(Undo::Alarm(state.alarm.take()));
+                Ok(DurableStorageResult::Ok)
+            }
+        }
+    }
+
+    fn check_keys(&self, keys: &[String]) -> Result<(), String> {
+        self.limits.check_keys(keys.len())?;
+        keys.iter().try_for_each(|key| self.limits.check_key(key))
+    }
+}
+
+impl OperationsHandler for MemoryDurableStorage {
+    fn handle_durable_storage(&self, op: DurableStorageOp) -> OpFuture<'_, DurableStorageResult> {
+        let result = self.execute(op);
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DurableListOptions;
+    use serde_json::json;
+
+    fn put(key: &str, value: serde_json::Value) -> DurableStorageOp {
+        DurableStorageOp::Put {
+            entries: vec![(key.to_string(), value)],
+        }
+    }
+
+    fn entries(storage: &MemoryDurableStorage) -> Vec<(String, serde_json::Value)> {
+        match storage.execute(DurableStorageOp::List {
+            options: DurableListOptions::default(),
+        }) {
+            DurableStorageResult::Entries(entries) => entries,
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn failed_transaction_changes_nothing() {
+        let storage = MemoryDurableStorage::new();
+        storage.execute(put("a", json!(1)));
+        storage.execute(DurableStorageOp::SetAlarm { scheduled_time: 5 });
+
+        let result = storage.execute(DurableStorageOp::Transaction {
+            ops: vec![
+                put("a", json!(2)),
+                put("b", json!(3)),
+                DurableStorageOp::Transaction {
+                    ops: vec![
+                        DurableStorageOp::Delete {
+                            keys: vec!["a".into()],
+                        },
+                        DurableStorageOp::DeleteAll,
+                        DurableStorageOp::SetAlarm { scheduled_time: 9 },
+                    ],
+                },
+                put("c", json!(4)),
+                put("", json!(5)),
+            ],
+        });
+
+        assert!(
+            matches!(result, DurableStorageResult::Error(err) if err.starts_with("Operation 4: "))
+        );
+        assert_eq!(entries(&storage), vec![("a".to_string(), json!(1))]);
+        assert_eq!(storage.alarm(), Some(5));
+    }
+
+    #[test]
+    fn transaction_applies_every_operation() {
+        let storage = MemoryDurableStorage::new();
+        storage.execute(put("a", json!(1)));
+
+        let result = storage.execute(DurableStorageOp::Transaction {
+            ops: vec![
+                DurableStorageOp::DeleteAll,
+                put("b", json!(2)),
+                DurableStorageOp::Delete {
+                    keys: vec!["a".into(), "b".into()],
+                },
+                put("c", json!(3)),
+            ],
+        });
+
+        assert!(matches!(result, DurableStorageResult::Transaction(results) if results.len() == 4));
+        assert_eq!(entries(&storage), vec![("c".to_string(), json!(3))]);
+    }
+}
@@ -0,0 +1,294 @@
+//! In-memory KV store
+//!
+//! Reference implementation of `KvOp` semantics:
+//! - Keys are non-empty UTF-8 strings of at most `max_key_size` bytes
+//! - Values are JSON, at most `max_value_size` bytes once serialized
+//! - `expires_in` is a TTL in seconds, at least `min_expiration_ttl`
+//! - Expired keys behave exactly like missing keys
+//! - `List` returns keys in lexicographic (byte) order, at most
+//!   `max_list_limit` keys per call, resuming after `cursor` if set
+//! - Each binding name is an independent namespace
+
+use crate::{KvLimits, KvOp, KvResult, OpFuture, OperationsHandler};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[derive(Debug)]
+struct Entry {
+    value: serde_json::Value,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// In-memory KV store implementing `handle_binding_kv`
+///
+/// Intended for tests and local development. All data is lost on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// let kv = MemoryKv::new();
+/// let ops: OperationsHandle = Arc::new(kv);
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryKv {
+    limits: KvLimits,
+    namespaces: Mutex<HashMap<String, BTreeMap<String, Entry>>>,
+}
+
+impl MemoryKv {
+    /// Create an empty store with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty store with custom limits
+    pub fn with_limits(limits: KvLimits) -> Self {
+        Self {
+            limits,
+            namespaces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limits enforced by this store
+    pub fn limits(&self) -> &KvLimits {
+        &self.limits
+    }
+
+    /// Execute a KV operation against a binding
+    pub fn execute(&self, binding: &str, op: KvOp) -> KvResult {
+        let now = Instant::now();
+        let mut namespaces = self.namespaces.lock().unwrap();
+
+        match op {
+            KvOp::Get { key } => {
+                if let Err(err) = self.limits.check_key(&key) {
+                    return KvResult::Error(err);
+                }
+
+                let Some(entries) = namespaces.get_mut(binding) else {
+                    return KvResult::Value(None);
+                };
+
+                match entries.get(&key) {
+                    Some(entry) if entry.is_expired(now) => {
+                        entries.remove(&key);
+                        KvResult::Value(None)
+                    }
+                    Some(entry) => KvResult::Value(Some(entry.value.clone())),
+                    None => KvResult::Value(None),
+                }
+            }
+
+            KvOp::Put {
+                key,
+                value,
+                expires_in,
+            } => {
+                if let Err(err) = self.limits.check_key(&key) {
+                    return KvResult::Error(err);
+                }
+
+                if let Err(err) = self.limits.check_value(&value) {
+                    return KvResult::Error(err);
+                }
+
+                let expires_at = match self.limits.check_expires_in(expires_in) {
+                    // Too far in the future to represent: never expires
+                    Ok(ttl) => ttl.and_then(|ttl| now.checked_add(ttl)),
+                    Err(err) => return KvResult::Error(err),
+                };
+
+                namespaces
+                    .entry(binding.to_string())
+                    .or_default()
+                    .insert(key, Entry { value, expires_at });
+
+                KvResult::Ok
+            }
+
+            KvOp::Delete { key } => {
+                if let Err(err) = self.limits.check_key(&key) {
+                    return KvResult::Error(err);
+                }
+
+                if let Some(entries) = namespaces.get_mut(binding) {
+                    entries.remove(&key);
+                }
+
+                KvResult::Ok
+            }
+
+            KvOp::List {
+                prefix,
+                limit,
+                cursor,
+            } => {
+                let Some(entries) = namespaces.get_mut(binding) else {
+                    return KvResult::Keys(Vec::new());
+                };
+
+                entries.retain(|_, entry| !entry.is_expired(now));
+
+                let limit = self.limits.list_limit(limit) as usize;
+                let prefix = prefix.unwrap_or_default();
+
+                let start = match cursor {
+                    Some(cursor) if cursor >= prefix => Bound::Excluded(cursor),
+                    _ => Bound::Included(prefix.clone()),
+                };
+
+                let keys = entries
+                    .range((start, Bound::Unbounded))
+                    .take_while(|(key, _)| key.starts_with(&prefix))
+                    .take(limit)
+                    .map(|(key, _)| key.clone())
+                    .collect();
+
+                KvResult::Keys(keys)
+            }
+        }
+    }
+}
+
+impl OperationsHandler for MemoryKv {
+    fn handle_binding_kv(&self, binding: &str, op: KvOp) -> OpFuture<'_, KvResult> {
+        let result = self.execute(binding, op);
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn put(kv: &MemoryKv, key: &str, expires_in: Option<u64>) -> KvResult {
+        kv.execute(
+            "KV",
+            KvOp::Put {
+                key: key.into(),
+                value: json!(key),
+                expires_in,
+            },
+        )
+    }
+
+    fn get(kv: &MemoryKv, key: &str) -> Option<serde_json::Value> {
+        match kv.execute("KV", KvOp::Get { key: key.into() }) {
+            KvResult::Value(value) => value,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    fn list(kv: &MemoryKv, prefix: Option<&str>, limit: u32, cursor: Option<&str>) -> Vec<String> {
+        let op = KvOp::List {
+            prefix: prefix.map(Into::into),
+            limit: Some(limit),
+            cursor: cursor.map(Into::into),
+        };
+
+        match kv.execute("KV", op) {
+            KvResult::Keys(keys) => keys,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    fn error(result: KvResult) -> String {
+        match result {
+            KvResult::Error(err) => err,
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expired_keys_are_missing() {
+        let kv = MemoryKv::with_limits(KvLimits {
+            min_expiration_ttl: 0,
+            ..KvLimits::default()
+        });
+
+        put(&kv, "expired", Some(0));
+        put(&kv, "live", Some(3600));
+        put(&kv, "forever", None);
+
+        assert_eq!(get(&kv, "expired"), None);
+        assert_eq!(get(&kv, "live"), Some(json!("live")));
+        assert_eq!(list(&kv, None, 10, None), ["forever", "live"]);
+    }
+
+    #[test]
+    fn list_pages_through_prefix() {
+        let kv = MemoryKv::new();
+
+        for key in ["a", "b/1", "b/2", "b/3", "b/4", "b/5", "c"] {
+            put(&kv, key, None);
+        }
+
+        assert_eq!(list(&kv, Some("b/"), 2, None), ["b/1", "b/2"]);
+        assert_eq!(list(&kv, Some("b/"), 2, Some("b/2")), ["b/3", "b/4"]);
+        assert_eq!(list(&kv, Some("b/"), 2, Some("b/4")), ["b/5"]);
+        assert!(list(&kv, Some("b/"), 2, Some("b/5")).is_empty());
+
+        // A cursor before the prefix does not widen the listing
+        assert_eq!(list(&kv, Some("b/"), 10, Some("a")).len(), 5);
+        assert_eq!(list(&kv, None, 3, Some("b/5")), ["c"]);
+    }
+
+    #[test]
+    fn list_is_capped_by_max_list_limit() {
+        let kv = MemoryKv::with_limits(KvLimits {
+            max_list_limit: 2,
+            ..KvLimits::default()
+        });
+
+        for key in ["a", "b", "c"] {
+            put(&kv, key, None);
+        }
+
+        assert_eq!(list(&kv, None, 100, None), ["a", "b"]);
+    }
+
+    #[test]
+    fn namespaces_are_independent() {
+        let kv = MemoryKv::new();
+
+        put(&kv, "key", None);
+
+        let other = kv.execute("OTHER", KvOp::Get { key: "key".into() });
+        assert!(matches!(other, KvResult::Value(None)));
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        let kv = MemoryKv::with_limits(KvLimits {
+            max_key_size: 4,
+            max_value_size: 8,
+            ..KvLimits::default()
+        });
+
+        assert!(error(put(&kv, "", None)).contains("empty"));
+        assert!(error(put(&kv, "too long", None)).contains("Key too large"));
+        assert!(error(put(&kv, "key", Some(59))).contains("expires_in"));
+
+        let large = kv.execute(
+            "KV",
+            KvOp::Put {
+                key: "key".into(),
+                value: json!("123456789"),
+                expires_in: None,
+            },
+        );
+        assert!(error(large).contains("Value too large"));
+
+        // Nothing was stored
+        assert!(list(&kv, None, 10, None).is_empty());
+    }
+}
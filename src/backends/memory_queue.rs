@@ -0,0 +1,255 @@
+//! In-memory message queue
+//!
+//! Reference implementation of `QueueOp` semantics and queue delivery:
+//! - Message bodies, delays and batch sizes are checked against
+//!   `QueueLimits`; a batch is accepted entirely or not at all
+//! - Delayed messages are delivered once their delay has elapsed
+//! - Messages are delivered in send order, at most `max_batch_size` at a time
+//! - Delivered messages stay in flight until their batch is settled
+//! - Retried messages are delivered again after their retry delay, with
+//!   `attempts` incremented; once `max_retries` retries have failed too
+//!   (`max_retries + 1` deliveries), they move to the dead letters
+//! - Each binding name is an independent queue
+
+use crate::{
+    OpFuture, OperationsHandler, QueueBatchResult, QueueLimits, QueueMessage, QueueOp,
+    QueueOutcome, QueueResult, QueueSend,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+struct Pending {
+    message: QueueMessage,
+    visible_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    /// Messages waiting for delivery, in send order
+    pending: VecDeque<Pending>,
+    /// Delivered messages waiting for an outcome, by id
+    in_flight: HashMap<String, QueueMessage>,
+    dead_letters: Vec<QueueMessage>,
+}
+
+/// In-memory queue implementing `handle_binding_queue`, with a consumer API
+///
+/// Intended for tests and local development. All messages are lost on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// let queue = Arc::new(MemoryQueue::new());
+/// // Producer: workers send through the operations handle
+/// let ops: OperationsHandle = queue.clone();
+/// // Consumer: deliver batches to a worker and settle them
+/// let messages = queue.receive("MY_QUEUE");
+/// let (event, rx) = Event::queue("MY_QUEUE".into(), messages.clone());
+/// worker.exec(event).await?;
+/// queue.settle("MY_QUEUE", &messages, &rx.await?);
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryQueue {
+    limits: QueueLimits,
+    next_id: AtomicU64,
+    queues: Mutex<HashMap<String, Queue>>,
+}
+
+impl MemoryQueue {
+    /// Create an empty queue with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty queue with custom limits
+    pub fn with_limits(limits: QueueLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Limits enforced by this queue
+    pub fn limits(&self) -> &QueueLimits {
+        &self.limits
+    }
+
+    /// Execute a queue operation against a binding
+    pub fn execute(&self, binding: &str, op: QueueOp) -> QueueResult {
+        let messages = match op {
+            QueueOp::Send { message } => vec![message],
+            QueueOp::SendBatch { messages } => {
+                if let Err(err) = self.limits.check_batch(messages.len()) {
+                    return QueueResult::Error(err);
+                }
+
+                messages
+            }
+        };
+
+        // Validate everything first: batches are all or nothing
+        for message in &messages {
+            if let Err(err) = self.limits.check_message(message) {
+                return QueueResult::Error(err);
+            }
+        }
+
+        let now = Instant::now();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        let mut queues = self.queues.lock().unwrap();
+        let queue = queues.entry(binding.to_string()).or_default();
+
+        for QueueSend {
+            body,
+            delay_seconds,
+        } in messages
+        {
+            queue.pending.push_back(Pending {
+                message: QueueMessage {
+                    id: self.new_id(),
+                    timestamp,
+                    body,
+                    attempts: 1,
+                },
+                visible_at: delayed(now, delay_seconds),
+            });
+        }
+
+        QueueResult::Ok
+    }
+
+    /// Take the next batch of deliverable messages (empty if none is ready)
+    pub fn receive(&self, binding: &str) -> Vec<QueueMessage> {
+        let now = Instant::now();
+        let mut queues = self.queues.lock().unwrap();
+
+        let Some(queue) = queues.get_mut(binding) else {
+            return Vec::new();
+        };
+
+        let mut batch = Vec::new();
+        let mut waiting = VecDeque::with_capacity(queue.pending.len());
+
+        for pending in queue.pending.drain(..) {
+            if batch.len() < self.limits.max_batch_size && pending.visible_at <= now {
+                batch.push(pending.message);
+            } else {
+                waiting.push_back(pending);
+            }
+        }
+
+        queue.pending = waiting;
+
+        for message in &batch {
+            queue.in_flight.insert(message.id.clone(), message.clone());
+        }
+
+        batch
+    }
+
+    /// Apply the outcome of a delivered batch
+    pub fn settle(&self, binding: &str, messages: &[QueueMessage], result: &QueueBatchResult) {
+        let now = Instant::now();
+        let mut queues = self.queues.lock().unwrap();
+
+        let Some(queue) = queues.get_mut(binding) else {
+            return;
+        };
+
+        for message in messages {
+            // Already settled (or never delivered)
+            let Some(mut message) = queue.in_flight.remove(&message.id) else {
+                continue;
+            };
+
+            let QueueOutcome::Retry { delay_seconds } = result.outcome(&message.id) else {
+                continue;
+            };
+
+            if message.attempts > self.limits.max_retries {
+                queue.dead_letters.push(message);
+                continue;
+            }
+
+            message.attempts += 1;
+
+            let delay_seconds = delay_seconds.map(|delay| delay.min(self.limits.max_delay_seconds));
+
+            queue.pending.push_back(Pending {
+                message,
+                visible_at: delayed(now, delay_seconds),
+            });
+        }
+    }
+
+    /// Number of messages waiting for delivery (including delayed ones)
+    pub fn pending(&self, binding: &str) -> usize {
+        let queues = self.queues.lock().unwrap();
+        queues.get(binding).map_or(0, |queue| queue.pending.len())
+    }
+
+    /// Messages dropped after too many failed deliveries
+    pub fn dead_letters(&self, binding: &str) -> Vec<QueueMessage> {
+        let queues = self.queues.lock().unwrap();
+        queues
+            .get(binding)
+            .map(|queue| queue.dead_letters.clone())
+            .unwrap_or_default()
+    }
+
+    fn new_id(&self) -> String {
+        format!("{:016x}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+/// Instant a message becomes deliverable
+fn delayed(now: Instant, delay_seconds: Option<u32>) -> Instant {
+    let delay = Duration::from_secs(delay_seconds.unwrap_or(0) as u64);
+    now.checked_add(delay).unwrap_or(now)
+}
+
+impl OperationsHandler for MemoryQueue {
+    fn handle_binding_queue(&self, binding: &str, op: QueueOp) -> OpFuture<'_, QueueResult> {
+        let result = self.execute(binding, op);
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueueBody;
+
+    #[test]
+    fn retries_before_dead_letters() {
+        let queue = MemoryQueue::new();
+        let send = QueueSend::new(QueueBody::Text("hello".into()));
+        assert!(matches!(
+            queue.execute("Q", QueueOp::Send { message: send }),
+            QueueResult::Ok
+        ));
+
+        // First delivery, then `max_retries` retries
+        for attempts in 1..=queue.limits().max_retries + 1 {
+            let messages = queue.receive("Q");
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].attempts, attempts);
+
+            queue.settle("Q", &messages, &QueueBatchResult::err("failed"));
+        }
+
+        assert!(queue.receive("Q").is_empty());
+        assert_eq!(queue.pending("Q"), 0);
+
+        let dead_letters = queue.dead_letters("Q");
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].attempts, 4);
+    }
+}
@@ -0,0 +1,325 @@
+//! In-memory rate limiter
+//!
+//! Reference implementation of `BindingRateLimit` semantics:
+//! - Each binding has its own `RateLimitConfig`, validated when the binding
+//!   is added; unknown bindings are errors
+//! - Keys are counted independently; a call is allowed if its whole cost
+//!   fits, and only allowed calls spend units
+//! - A cost of 0 reads the state of a key without spending anything
+//! - Token bucket: `limit` tokens, refilled continuously at
+//!   `limit / period_seconds` per second
+//! - Sliding window: windows of `period_seconds` aligned on the limiter
+//!   creation; the previous window counts in proportion to its overlap
+//!   with the last `period_seconds`
+//! - Idle keys are forgotten once they are back to a full limit
+
+use crate::{OpFuture, OperationsHandler, RateLimitAlgorithm, RateLimitConfig, RateLimitOutcome};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Keys per binding above which idle keys are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Debug)]
+enum State {
+    Bucket {
+        tokens: f64,
+        updated: Instant,
+    },
+    Window {
+        /// Index of the current window since the limiter epoch
+        index: u64,
+        current: u64,
+        previous: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Limiter {
+    config: RateLimitConfig,
+    keys: HashMap<String, State>,
+}
+
+/// In-memory rate limiter implementing `handle_binding_rate_limit`
+///
+/// Intended for tests and local development. All counters are lost on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// let limiter = MemoryRateLimiter::new()
+///     .with_binding("API_LIMITER", RateLimitConfig::new(100, 60))?;
+/// let ops: OperationsHandle = Arc::new(limiter);
+/// ```
+#[derive(Debug)]
+pub struct MemoryRateLimiter {
+    epoch: Instant,
+    limiters: Mutex<HashMap<String, Limiter>>,
+}
+
+impl Default for MemoryRateLimiter {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            limiters: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl MemoryRateLimiter {
+    /// Create a limiter without bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configure a binding (replaces its configuration and counters)
+    ///
+    /// Fails if the configuration is invalid (see [`RateLimitConfig::check`]).
+    pub fn with_binding(
+        self,
+        binding: impl Into<String>,
+        config: RateLimitConfig,
+    ) -> Result<Self, String> {
+        config.check()?;
+
+        self.limiters.lock().unwrap().insert(
+            binding.into(),
+            Limiter {
+                config,
+                keys: HashMap::new(),
+            },
+        );
+        Ok(self)
+    }
+
+    /// Spend `cost` units for `key` on a binding
+    pub fn execute(&self, binding: &str, key: &str, cost: u64) -> Result<RateLimitOutcome, String> {
+        self.execute_at(binding, key, cost, Instant::now())
+    }
+
+    fn execute_at(
+        &self,
+        binding: &str,
+        key: &str,
+        cost: u64,
+        now: Instant,
+    ) -> Result<RateLimitOutcome, String> {
+        let mut limiters = self.limiters.lock().unwrap();
+
+        let limiter = limiters
+            .get_mut(binding)
+            .ok_or_else(|| format!("Rate limit binding '{}' not configured", binding))?;
+
+        let config = &limiter.config;
+        let limit = config.limit as f64;
+        let period = config.period_seconds as f64;
+        let elapsed = now.duration_since(self.epoch).as_secs_f64();
+        let index = (elapsed / period) as u64;
+
+        if limiter.keys.len() > PRUNE_THRESHOLD {
+            limiter.keys.retain(|_, state| match state {
+                State::Bucket { tokens, updated } => {
+                    *tokens + now.duration_since(*updated).as_secs_f64() * limit / period < limit
+                }
+                State::Window { index: i, .. } => *i + 1 >= index,
+            });
+        }
+
+        let state = limiter
+            .keys
+            .entry(key.to_string())
+            .or_insert(match config.algorithm {
+                RateLimitAlgorithm::TokenBucket => State::Bucket {
+                    tokens: limit,
+                    updated: now,
+                },
+                RateLimitAlgorithm::SlidingWindow => State::Window {
+                    index,
+                    current: 0,
+                    previous: 0,
+                },
+            });
+
+        let outcome = match state {
+            State::Bucket { tokens, updated } => {
+                let rate = limit / period;
+                *tokens = (*tokens + now.duration_since(*updated).as_secs_f64() * rate).min(limit);
+                *updated = now;
+
+                let allowed = cost as f64 <= *tokens;
+
+                if allowed {
+                    *tokens -= cost as f64;
+                }
+
+                RateLimitOutcome {
+                    allowed,
+                    remaining: tokens.floor() as u64,
+                    reset: ((limit - *tokens) / rate).ceil() as u64,
+                }
+            }
+
+            State::Window {
+                index: window,
+                current,
+                previous,
+            } => {
+                if index > *window {
+                    *previous = if index == *window + 1 { *current } else { 0 };
+                    *current = 0;
+                    *window = index;
+                }
+
+                let into_window = elapsed - index as f64 * period;
+                let weight = 1.0 - into_window / period;
+                let used = |current: u64| *previous as f64 * weight + current as f64;
+
+                let allowed = used(*current) + cost as f64 <= limit;
+
+                if allowed {
+                    *current += cost;
+                }
+
+                // The previous window stops counting when the current one
+                // ends, and the current one a period later
+                let reset = if *current > 0 {
+                    2.0 * period - into_window
+                } else if *previous > 0 {
+                    period - into_window
+                } else {
+                    0.0
+                };
+
+                RateLimitOutcome {
+                    allowed,
+                    remaining: (limit - used(*current)).max(0.0).floor() as u64,
+                    reset: reset.ceil() as u64,
+                }
+            }
+        };
+
+        Ok(outcome)
+    }
+}
+
+impl OperationsHandler for MemoryRateLimiter {
+    fn handle_binding_rate_limit(
+        &self,
+        binding: &str,
+        key: &str,
+        cost: u64,
+    ) -> OpFuture<'_, Result<RateLimitOutcome, String>> {
+        let result = self.execute(binding, key, cost);
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(config: RateLimitConfig) -> MemoryRateLimiter {
+        MemoryRateLimiter::new()
+            .with_binding("LIMITER", config)
+            .unwrap()
+    }
+
+    /// Spend `cost` for `key`, `secs` after the limiter creation
+    fn spend(limiter: &MemoryRateLimiter, key: &str, cost: u64, secs: f64) -> RateLimitOutcome {
+        let now = limiter.epoch + Duration::from_secs_f64(secs);
+        limiter.execute_at("LIMITER", key, cost, now).unwrap()
+    }
+
+    fn outcome(allowed: bool, remaining: u64, reset: u64) -> RateLimitOutcome {
+        RateLimitOutcome {
+            allowed,
+            remaining,
+            reset,
+        }
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        for config in [RateLimitConfig::new(0, 60), RateLimitConfig::new(10, 0)] {
+            assert!(
+                MemoryRateLimiter::new()
+                    .with_binding("LIMITER", config)
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_binding_is_an_error() {
+        let limiter = limiter(RateLimitConfig::new(10, 60));
+
+        let err = limiter.execute("OTHER", "key", 1).unwrap_err();
+        assert!(err.contains("OTHER"));
+    }
+
+    #[test]
+    fn token_bucket_allows_up_to_limit() {
+        let limiter = limiter(RateLimitConfig::new(10, 10));
+
+        assert_eq!(spend(&limiter, "a", 9, 0.0), outcome(true, 1, 9));
+        assert_eq!(spend(&limiter, "a", 1, 0.0), outcome(true, 0, 10));
+
+        // Denied calls spend nothing, keys are independent
+        assert_eq!(spend(&limiter, "a", 1, 0.0), outcome(false, 0, 10));
+        assert_eq!(spend(&limiter, "b", 10, 0.0), outcome(true, 0, 10));
+        assert_eq!(spend(&limiter, "b", 11, 0.0), outcome(false, 0, 10));
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let limiter = limiter(RateLimitConfig::new(10, 10));
+
+        spend(&limiter, "a", 10, 0.0);
+
+        // One token per second, read without spending
+        assert_eq!(spend(&limiter, "a", 0, 3.0), outcome(true, 3, 7));
+        assert_eq!(spend(&limiter, "a", 0, 3.0), outcome(true, 3, 7));
+        assert_eq!(spend(&limiter, "a", 4, 3.0), outcome(false, 3, 7));
+        assert_eq!(spend(&limiter, "a", 3, 3.0), outcome(true, 0, 10));
+
+        // Never above the limit
+        assert_eq!(spend(&limiter, "a", 0, 100.0), outcome(true, 10, 0));
+    }
+
+    #[test]
+    fn sliding_window_weights_previous_window() {
+        let config = RateLimitConfig::new(10, 60).with_algorithm(RateLimitAlgorithm::SlidingWindow);
+        let limiter = limiter(config);
+
+        assert_eq!(spend(&limiter, "a", 10, 30.0), outcome(true, 0, 90));
+        assert_eq!(spend(&limiter, "a", 1, 30.0), outcome(false, 0, 90));
+
+        // Halfway through the next window, the previous one counts for half
+        assert_eq!(spend(&limiter, "a", 0, 90.0), outcome(true, 5, 30));
+        assert_eq!(spend(&limiter, "a", 6, 90.0), outcome(false, 5, 30));
+        assert_eq!(spend(&limiter, "a", 5, 90.0), outcome(true, 0, 90));
+
+        // Windows older than the previous one don't count
+        assert_eq!(spend(&limiter, "a", 0, 200.0), outcome(true, 10, 0));
+    }
+
+    #[test]
+    fn idle_keys_are_pruned() {
+        let limiter = limiter(RateLimitConfig::new(10, 10));
+
+        for i in 0..=PRUNE_THRESHOLD {
+            spend(&limiter, &i.to_string(), 1, 0.0);
+        }
+        spend(&limiter, "busy", 10, 5.0);
+
+        // Every key but `busy` is back to a full bucket
+        spend(&limiter, "new", 1, 10.0);
+
+        let limiters = limiter.limiters.lock().unwrap();
+        let mut keys: Vec<_> = limiters["LIMITER"].keys.keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, ["busy", "new"]);
+    }
+}
@@ -0,0 +1,807 @@
+//! Reference backends for runner operations
+//!
+//! Self-contained implementations of `OperationsHandler` binding methods,
+//! each behind its own feature flag. They serve as test fixtures for
+//! runtimes and runners, and as executable specifications of the
+//! semantics of each operation type.
+
+#[cfg(feature = "fs")]
+mod fs;
+#[cfg(feature = "memory")]
+mod memory_cache;
+#[cfg(feature = "memory")]
+mod memory_durable;
+#[cfg(feature = "memory")]
+mod memory_kv;
+#[cfg(feature = "memory")]
+mod memory_queue;
+#[cfg(feature = "memory")]
+mod memory_rate_limit;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "fs")]
+pub use fs::FsBackend;
+#[cfg(feature = "memory")]
+pub use memory_cache::MemoryCache;
+#[cfg(feature = "memory")]
+pub use memory_durable::MemoryDurableStorage;
+#[cfg(feature = "memory")]
+pub use memory_kv::MemoryKv;
+#[cfg(feature = "memory")]
+pub use memory_queue::MemoryQueue;
+#[cfg(feature = "memory")]
+pub use memory_rate_limit::MemoryRateLimiter;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+
+/// Content hash used as object etag (FNV-1a 64, hex)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn etag(data: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    format!("{:016x}", hash)
+}
+
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+type Hasher = Box<dyn sha2::digest::DynDigest + Send>;
+
+/// Incremental verification of the checksums supplied with a put
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) struct ChecksumVerifier {
+    /// Algorithm name, expected checksum (lowercase hex) and hasher
+    hashers: Vec<(&'static str, String, Hasher)>,
+}
+
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+impl ChecksumVerifier {
+    /// Hash only the algorithms with an expected checksum
+    pub fn new(expected: &crate::Checksums) -> Self {
+        use sha2::Digest;
+
+        let algorithms: [(_, _, fn() -> Hasher); 5] = [
+            ("MD5", &expected.md5, || Box::new(md5::Md5::new())),
+            ("SHA-1", &expected.sha1, || Box::new(sha1::Sha1::new())),
+            (
+                "SHA-256",
+                &expected.sha256,
+                || Box::new(sha2::Sha256::new()),
+            ),
+            (
+                "SHA-384",
+                &expected.sha384,
+                || Box::new(sha2::Sha384::new()),
+            ),
+            (
+                "SHA-512",
+                &expected.sha512,
+                || Box::new(sha2::Sha512::new()),
+            ),
+        ];
+
+        let hashers = algorithms
+            .into_iter()
+            .filter_map(|(name, expected, hasher)| {
+                Some((name, expected.as_ref()?.to_ascii_lowercase(), hasher()))
+            })
+            .collect();
+
+        Self { hashers }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for (_, _, hasher) in &mut self.hashers {
+            hasher.update(data);
+        }
+    }
+
+    /// Compare the computed checksums with the expected ones
+    pub fn verify(self) -> Result<(), String> {
+        for (name, expected, hasher) in self.hashers {
+            let computed = hex::encode(hasher.finalize());
+
+            if computed != expected {
+                return Err(format!(
+                    "{} checksum mismatch: expected {}, computed {}",
+                    name, expected, computed
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Verify the checksums supplied with a buffered put
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_checksums(expected: &crate::Checksums, body: &[u8]) -> Result<(), String> {
+    let mut verifier = ChecksumVerifier::new(expected);
+    verifier.update(body);
+    verifier.verify()
+}
+
+/// Reject checksums on multipart uploads (the parts are not verified)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_multipart_options(options: &crate::PutOptions) -> Result<(), String> {
+    if !options.checksums.is_empty() {
+        return Err("Checksums are not supported for multipart uploads".into());
+    }
+
+    Ok(())
+}
+
+/// Response headers for a stored object (HTTP metadata and validators)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+fn object_headers(metadata: &crate::ObjectMetadata) -> Vec<(String, String)> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let mut headers = metadata.http_metadata.to_headers();
+
+    if metadata.http_metadata.content_type.is_none() {
+        headers.push(("content-type".into(), "application/octet-stream".into()));
+    }
+
+    if let Some(expiry) = metadata.http_metadata.cache_expiry {
+        let expires = UNIX_EPOCH + Duration::from_millis(expiry);
+        headers.push(("expires".into(), httpdate::fmt_http_date(expires)));
+    }
+
+    let uploaded = UNIX_EPOCH + Duration::from_millis(metadata.uploaded);
+
+    headers.push(("etag".into(), metadata.http_etag()));
+    headers.push(("last-modified".into(), httpdate::fmt_http_date(uploaded)));
+
+    headers
+}
+
+/// Response for an object body (200, or 206 for a range)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn object_response(
+    metadata: &crate::ObjectMetadata,
+    range: Option<crate::ContentRange>,
+    body: crate::ResponseBody,
+) -> crate::HttpResponse {
+    let mut headers = object_headers(metadata);
+    headers.push(("accept-ranges".into(), "bytes".into()));
+
+    let status = match range {
+        Some(range) => {
+            headers.push(("content-length".into(), range.length.to_string()));
+            headers.push(("content-range".into(), range.header_value()));
+            206
+        }
+        None => {
+            headers.push(("content-length".into(), metadata.size.to_string()));
+            200
+        }
+    };
+
+    crate::HttpResponse {
+        status,
+        headers,
+        body,
+    }
+}
+
+/// Response for a get whose conditions or range were rejected (304, 412, 416)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn rejection_response(
+    metadata: &crate::ObjectMetadata,
+    rejection: crate::ReadRejection,
+) -> crate::HttpResponse {
+    use crate::ReadRejection;
+
+    let headers = match rejection {
+        ReadRejection::NotModified => object_headers(metadata)
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("content-"))
+            .collect(),
+        ReadRejection::PreconditionFailed => vec![],
+        ReadRejection::RangeNotSatisfiable => {
+            vec![("content-range".into(), format!("bytes */{}", metadata.size))]
+        }
+    };
+
+    crate::HttpResponse {
+        status: rejection.http_status(),
+        headers,
+        body: crate::ResponseBody::None,
+    }
+}
+
+/// Storage result for a get whose conditions or range were rejected
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn rejection_result(
+    metadata: crate::ObjectMetadata,
+    rejection: crate::ReadRejection,
+) -> crate::StorageResult {
+    use crate::{ReadRejection, StorageResult};
+
+    match rejection {
+        ReadRejection::NotModified => StorageResult::PreconditionFailed {
+            metadata,
+            not_modified: true,
+        },
+        ReadRejection::PreconditionFailed => StorageResult::PreconditionFailed {
+            metadata,
+            not_modified: false,
+        },
+        ReadRejection::RangeNotSatisfiable => StorageResult::RangeNotSatisfiable { metadata },
+    }
+}
+
+/// Maximum (and default) number of entries returned by a storage `List`
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+const LIST_LIMIT: u32 = 1000;
+
+/// Keys and delimited prefixes of one storage `List` page
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+#[derive(Debug, Default)]
+pub(crate) struct ListPage {
+    pub keys: Vec<String>,
+    pub delimited_prefixes: Vec<String>,
+    pub truncated: bool,
+    /// Last key or prefix of the page, set when truncated
+    pub cursor: Option<String>,
+}
+
+/// Group sorted keys (all starting with the list prefix) into a `List` page
+///
+/// Keys sharing a delimited prefix are contiguous in sorted order, so each
+/// prefix is emitted once. Objects and prefixes both count towards the limit.
+///
+/// The cursor is the last key or prefix returned: resuming skips keys up to
+/// it and keys grouped under it.
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn list_page<I>(keys: I, options: &crate::ListOptions) -> Result<ListPage, String>
+where
+    I: IntoIterator<Item = Result<String, String>>,
+{
+    let limit = options.limit.unwrap_or(LIST_LIMIT).min(LIST_LIMIT) as usize;
+    let cursor = options.cursor.as_deref();
+    let mut page = ListPage::default();
+
+    for key in keys {
+        let key = key?;
+        let prefix = options.delimited_prefix(&key);
+
+        if let Some(cursor) = cursor
+            && (key.as_str() <= cursor || prefix == Some(cursor))
+        {
+            continue;
+        }
+
+        if let Some(prefix) = prefix
+            && page
+                .delimited_prefixes
+                .last()
+                .is_some_and(|last| last == prefix)
+        {
+            continue;
+        }
+
+        if page.keys.len() + page.delimited_prefixes.len() == limit {
+            page.truncated = true;
+            // Entries are emitted in order, so the greatest one is the last
+            let last = page.keys.last().max(page.delimited_prefixes.last());
+            page.cursor = last.cloned().or_else(|| options.cursor.clone());
+            break;
+        }
+
+        match prefix {
+            Some(prefix) => page.delimited_prefixes.push(prefix.to_string()),
+            None => page.keys.push(key),
+        }
+    }
+
+    Ok(page)
+}
+
+/// Validate the key list of a storage `Delete`
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_delete_keys(keys: &[String]) -> Result<(), String> {
+    if keys.len() > crate::DELETE_MAX_KEYS {
+        return Err(format!(
+            "Too many keys to delete: {} (max {})",
+            keys.len(),
+            crate::DELETE_MAX_KEYS
+        ));
+    }
+
+    Ok(())
+}
+
+/// Unique identifier for a new multipart upload
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn new_upload_id() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let seed = format!("{}:{}:{}", std::process::id(), nanos, count);
+
+    format!("{}{:016x}", etag(seed.as_bytes()), count)
+}
+
+/// Validate a part number of a multipart upload
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_part_number(part_number: u16) -> Result<(), String> {
+    if part_number == 0 || part_number > crate::MULTIPART_MAX_PARTS {
+        return Err(format!(
+            "Invalid part number: {} (must be 1 to {})",
+            part_number,
+            crate::MULTIPART_MAX_PARTS
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate the part list of a multipart completion (non-empty, ascending)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_completed_parts(parts: &[crate::UploadedPart]) -> Result<(), String> {
+    if parts.is_empty() {
+        return Err("Multipart upload must have at least one part".into());
+    }
+
+    for part in parts {
+        check_part_number(part.part_number)?;
+    }
+
+    if parts
+        .windows(2)
+        .any(|w| w[0].part_number >= w[1].part_number)
+    {
+        return Err("Parts must be in ascending part number order".into());
+    }
+
+    Ok(())
+}
+
+/// Validate the size of a part of a multipart completion
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn check_part_size(part_number: u16, size: u64, last: bool) -> Result<(), String> {
+    if !last && size < crate::MULTIPART_MIN_PART_SIZE {
+        return Err(format!(
+            "Part {} too small: {} bytes (min {} except for the last part)",
+            part_number,
+            size,
+            crate::MULTIPART_MIN_PART_SIZE
+        ));
+    }
+
+    Ok(())
+}
+
+/// Etag of an object assembled from parts (`<hash of part etags>-<count>`)
+#[cfg(any(feature = "sqlite", feature = "fs"))]
+pub(crate) fn multipart_etag(parts: &[crate::UploadedPart]) -> String {
+    let etags: String = parts.iter().map(|part| part.etag.as_str()).collect();
+    format!("{}-{}", etag(etags.as_bytes()), parts.len())
+}
+
+#[cfg(all(test, any(feature = "sqlite", feature = "fs")))]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        Checksums, GetOptions, HttpMetadata, ListInclude, ListOptions, MULTIPART_MIN_PART_SIZE,
+        ObjectMetadata, OperationsHandler, PutOptions, StorageOp, StorageRange, StorageResult,
+        UploadedPart,
+    };
+
+    async fn storage(ops: &dyn OperationsHandler, op: StorageOp) -> StorageResult {
+        ops.handle_binding_storage("BUCKET", op).await
+    }
+
+    fn put(key: &str, body: &[u8]) -> StorageOp {
+        StorageOp::Put {
+            key: key.into(),
+            body: body.to_vec(),
+            options: PutOptions::default(),
+        }
+    }
+
+    fn get(key: &str, options: GetOptions) -> StorageOp {
+        StorageOp::Get {
+            key: key.into(),
+            options,
+        }
+    }
+
+    /// Objects, delimited prefixes and cursor (set if truncated) of a `List`
+    async fn list_objects(
+        ops: &dyn OperationsHandler,
+        options: ListOptions,
+    ) -> (Vec<ObjectMetadata>, Vec<String>, Option<String>) {
+        let StorageResult::List {
+            objects,
+            delimited_prefixes,
+            truncated,
+            cursor,
+        } = storage(ops, StorageOp::List { options }).await
+        else {
+            panic!("expected a list");
+        };
+
+        assert_eq!(truncated, cursor.is_some());
+        (objects, delimited_prefixes, cursor)
+    }
+
+    /// Keys, delimited prefixes and cursor (set if truncated) of a `List`
+    async fn list(
+        ops: &dyn OperationsHandler,
+        options: ListOptions,
+    ) -> (Vec<String>, Vec<String>, Option<String>) {
+        let (objects, prefixes, cursor) = list_objects(ops, options).await;
+        let keys = objects.into_iter().map(|object| object.key).collect();
+
+        (keys, prefixes, cursor)
+    }
+
+    /// Get, put, head and list semantics shared by the storage backends
+    pub(crate) async fn storage_scenario(ops: &dyn OperationsHandler) {
+        for op in [
+            get("missing", GetOptions::default()),
+            StorageOp::Head {
+                key: "missing".into(),
+            },
+        ] {
+            assert!(matches!(storage(ops, op).await, StorageResult::NotFound));
+        }
+
+        let options = PutOptions {
+            http_metadata: HttpMetadata {
+                content_type: Some("text/plain".into()),
+                ..HttpMetadata::default()
+            },
+            custom_metadata: [("owner".to_string(), "alice".to_string())].into(),
+            ..PutOptions::default()
+        };
+        let op = StorageOp::Put {
+            key: "docs/a.txt".into(),
+            body: b"hello world".to_vec(),
+            options,
+        };
+        let StorageResult::Metadata(created) = storage(ops, op).await else {
+            panic!("expected metadata");
+        };
+        assert_eq!(created.key, "docs/a.txt");
+        assert_eq!(created.size, 11);
+
+        let op = StorageOp::Head {
+            key: "docs/a.txt".into(),
+        };
+        let StorageResult::Metadata(head) = storage(ops, op).await else {
+            panic!("expected metadata");
+        };
+        assert_eq!(head.etag, created.etag);
+        assert_eq!(
+            head.http_metadata.content_type.as_deref(),
+            Some("text/plain")
+        );
+        assert_eq!(head.custom_metadata["owner"], "alice");
+
+        let StorageResult::Object(object) =
+            storage(ops, get("docs/a.txt", GetOptions::default())).await
+        else {
+            panic!("expected an object");
+        };
+        assert_eq!(object.body, b"hello world");
+        assert_eq!(object.range, None);
+
+        let range = GetOptions {
+            range: Some(StorageRange::Suffix { suffix: 5 }),
+            ..GetOptions::default()
+        };
+        let StorageResult::Object(object) = storage(ops, get("docs/a.txt", range)).await else {
+            panic!("expected an object");
+        };
+        assert_eq!(object.body, b"world");
+
+        // Overwrite
+        let StorageResult::Metadata(metadata) = storage(ops, put("docs/a.txt", b"bye")).await
+        else {
+            panic!("expected metadata");
+        };
+        assert_eq!(metadata.size, 3);
+
+        for key in ["b", "docs/c.txt", "a"] {
+            storage(ops, put(key, key.as_bytes())).await;
+        }
+
+        let (keys, _, cursor) = list(ops, ListOptions::default()).await;
+        assert_eq!(keys, ["a", "b", "docs/a.txt", "docs/c.txt"]);
+        assert_eq!(cursor, None);
+
+        let options = ListOptions {
+            prefix: Some("docs/".into()),
+            limit: Some(1),
+            ..ListOptions::default()
+        };
+        let (keys, _, cursor) = list(ops, options).await;
+        assert_eq!(keys, ["docs/a.txt"]);
+        assert!(cursor.is_some());
+    }
+
+    /// Delimiter, include and cursor semantics of `List`
+    pub(crate) async fn list_scenario(ops: &dyn OperationsHandler) {
+        let options = PutOptions {
+            http_metadata: HttpMetadata {
+                content_type: Some("text/plain".into()),
+                ..HttpMetadata::default()
+            },
+            custom_metadata: [("owner".to_string(), "alice".to_string())].into(),
+            ..PutOptions::default()
+        };
+
+        for key in [
+            "a.txt",
+            "docs/a.txt",
+            "docs/b.txt",
+            "docs/sub/c.txt",
+            "docs0",
+            "img/x.png",
+            "z.txt",
+        ] {
+            let op = StorageOp::Put {
+                key: key.into(),
+                body: key.as_bytes().to_vec(),
+                options: options.clone(),
+            };
+            assert!(matches!(storage(ops, op).await, StorageResult::Metadata(_)));
+        }
+
+        let delimited = ListOptions {
+            delimiter: Some("/".into()),
+            ..ListOptions::default()
+        };
+        let (keys, prefixes, cursor) = list(ops, delimited.clone()).await;
+        assert_eq!(keys, ["a.txt", "docs0", "z.txt"]);
+        assert_eq!(prefixes, ["docs/", "img/"]);
+        assert_eq!(cursor, None);
+
+        let options = ListOptions {
+            prefix: Some("docs/".into()),
+            ..delimited.clone()
+        };
+        let (keys, prefixes, _) = list(ops, options).await;
+        assert_eq!(keys, ["docs/a.txt", "docs/b.txt"]);
+        assert_eq!(prefixes, ["docs/sub/"]);
+
+        // Pages of two entries, prefixes count towards the limit
+        let mut options = ListOptions {
+            limit: Some(2),
+            ..delimited
+        };
+        let mut pages = Vec::new();
+
+        loop {
+            let (keys, prefixes, cursor) = list(ops, options.clone()).await;
+            pages.push((keys, prefixes));
+
+            match cursor {
+                Some(cursor) => options.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            pages,
+            [
+                (vec!["a.txt".to_string()], vec!["docs/".to_string()]),
+                (vec!["docs0".to_string()], vec!["img/".to_string()]),
+                (vec!["z.txt".to_string()], vec![]),
+            ]
+        );
+
+        let mut options = ListOptions {
+            prefix: Some("docs/".into()),
+            limit: Some(2),
+            ..ListOptions::default()
+        };
+        let (keys, _, cursor) = list(ops, options.clone()).await;
+        assert_eq!(keys, ["docs/a.txt", "docs/b.txt"]);
+
+        options.cursor = cursor;
+        let (keys, _, cursor) = list(ops, options).await;
+        assert_eq!(keys, ["docs/sub/c.txt"]);
+        assert_eq!(cursor, None);
+
+        // Metadata is only included when requested
+        let (objects, _, _) = list_objects(ops, ListOptions::default()).await;
+        assert!(objects.iter().all(|object| {
+            object.http_metadata == HttpMetadata::default() && object.custom_metadata.is_empty()
+        }));
+
+        let options = ListOptions {
+            include: vec![ListInclude::HttpMetadata, ListInclude::CustomMetadata],
+            ..ListOptions::default()
+        };
+        let (objects, _, _) = list_objects(ops, options).await;
+        assert_eq!(objects.len(), 7);
+        assert!(objects.iter().all(|object| {
+            object.http_metadata.content_type.as_deref() == Some("text/plain")
+                && object.custom_metadata["owner"] == "alice"
+        }));
+
+        let options = ListOptions {
+            include: vec![ListInclude::CustomMetadata],
+            ..ListOptions::default()
+        };
+        let (objects, _, _) = list_objects(ops, options).await;
+        assert!(objects.iter().all(|object| {
+            object.http_metadata.content_type.is_none()
+                && object.custom_metadata["owner"] == "alice"
+        }));
+    }
+
+    /// `Copy` and batch `Delete` semantics shared by the storage backends
+    pub(crate) async fn copy_delete_scenario(ops: &dyn OperationsHandler) {
+        let copy = |from: &str, to: &str| StorageOp::Copy {
+            from: from.into(),
+            to: to.into(),
+        };
+
+        assert!(matches!(
+            storage(ops, copy("missing", "b")).await,
+            StorageResult::NotFound
+        ));
+
+        let options = PutOptions {
+            custom_metadata: [("owner".to_string(), "alice".to_string())].into(),
+            ..PutOptions::default()
+        };
+        let op = StorageOp::Put {
+            key: "a".into(),
+            body: b"original".to_vec(),
+            options,
+        };
+        storage(ops, op).await;
+        storage(ops, put("b", b"replaced")).await;
+
+        let StorageResult::Metadata(copied) = storage(ops, copy("a", "b")).await else {
+            panic!("expected metadata");
+        };
+        assert_eq!(copied.key, "b");
+        assert_eq!(copied.size, 8);
+        assert_eq!(copied.custom_metadata["owner"], "alice");
+
+        let StorageResult::Object(object) = storage(ops, get("b", GetOptions::default())).await
+        else {
+            panic!("expected an object");
+        };
+        assert_eq!(object.body, b"original");
+
+        // The source is left untouched
+        let StorageResult::Object(object) = storage(ops, get("a", GetOptions::default())).await
+        else {
+            panic!("expected an object");
+        };
+        assert_eq!(object.body, b"original");
+
+        storage(ops, put("dir/c", b"c")).await;
+
+        let op = StorageOp::Delete {
+            keys: vec!["a".into(), "dir/c".into(), "missing".into()],
+        };
+        assert!(matches!(storage(ops, op).await, StorageResult::Body(None)));
+
+        let (keys, _, _) = list(ops, ListOptions::default()).await;
+        assert_eq!(keys, ["b"]);
+
+        let op = StorageOp::Delete {
+            keys: vec!["k".to_string(); crate::DELETE_MAX_KEYS + 1],
+        };
+        assert!(matches!(storage(ops, op).await, StorageResult::Error(_)));
+        assert!(matches!(
+            storage(ops, StorageOp::Head { key: "b".into() }).await,
+            StorageResult::Metadata(_)
+        ));
+    }
+
+    async fn upload(
+        ops: &dyn OperationsHandler,
+        key: &str,
+        parts: &[&[u8]],
+    ) -> (String, Vec<UploadedPart>) {
+        let op = StorageOp::CreateMultipartUpload {
+            key: key.into(),
+            options: PutOptions::default(),
+        };
+        let StorageResult::MultipartUpload { upload_id, .. } = storage(ops, op).await else {
+            panic!("expected a multipart upload");
+        };
+
+        let mut uploaded = Vec::new();
+
+        for (index, body) in parts.iter().enumerate() {
+            let op = StorageOp::UploadPart {
+                key: key.into(),
+                upload_id: upload_id.clone(),
+                part_number: index as u16 + 1,
+                body: body.to_vec(),
+            };
+            let StorageResult::UploadedPart(part) = storage(ops, op).await else {
+                panic!("expected an uploaded part");
+            };
+            uploaded.push(part);
+        }
+
+        (upload_id, uploaded)
+    }
+
+    /// Multipart semantics shared by the storage backends
+    pub(crate) async fn multipart_scenario(ops: &dyn OperationsHandler) {
+        let first = vec![b'a'; MULTIPART_MIN_PART_SIZE as usize];
+
+        // Every part but the last must reach the minimum size
+        let (upload_id, parts) = upload(ops, "small", &[b"abc", b"def"]).await;
+        let op = StorageOp::CompleteMultipartUpload {
+            key: "small".into(),
+            upload_id,
+            parts,
+        };
+        let result = storage(ops, op).await;
+        assert!(matches!(result, StorageResult::Error(ref err) if err.contains("too small")));
+
+        let (upload_id, parts) = upload(ops, "big", &[&first, b"tail"]).await;
+        let etag = multipart_etag(&parts);
+        let op = StorageOp::CompleteMultipartUpload {
+            key: "big".into(),
+            upload_id,
+            parts,
+        };
+        let StorageResult::Metadata(metadata) = storage(ops, op).await else {
+            panic!("expected metadata");
+        };
+        assert_eq!(metadata.size, MULTIPART_MIN_PART_SIZE + 4);
+        assert_eq!(metadata.etag, etag);
+        assert!(etag.ends_with("-2"));
+
+        let op = StorageOp::Head { key: "big".into() };
+        let result = storage(ops, op).await;
+        assert!(matches!(result, StorageResult::Metadata(ref head) if head.etag == etag));
+
+        let op = StorageOp::Get {
+            key: "big".into(),
+            options: GetOptions::default(),
+        };
+        let StorageResult::Object(object) = storage(ops, op).await else {
+            panic!("expected an object");
+        };
+        assert_eq!(object.body.len() as u64, MULTIPART_MIN_PART_SIZE + 4);
+        assert!(object.body.ends_with(b"atail"));
+    }
+
+    #[test]
+    fn checksums_are_verified() {
+        let expected = Checksums {
+            md5: Some("900150983cd24fb0d6963f7d28e17f72".into()),
+            sha1: Some("A9993E364706816ABA3E25717850C26C9CD0D89D".into()),
+            sha256: Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into()),
+            ..Checksums::default()
+        };
+
+        assert!(check_checksums(&expected, b"abc").is_ok());
+        assert!(check_checksums(&Checksums::default(), b"anything").is_ok());
+
+        let err = check_checksums(&expected, b"abd").unwrap_err();
+        assert!(err.starts_with("MD5 checksum mismatch"), "{}", err);
+
+        let mut verifier = ChecksumVerifier::new(&expected);
+        verifier.update(b"a");
+        verifier.update(b"bc");
+        assert!(verifier.verify().is_ok());
+    }
+}
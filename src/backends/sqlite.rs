@@ -0,0 +1,968 @@
+//! SQLite-backed KV, storage and database
+//!
+//! Persists bindings to a single local SQLite file so that state survives
+//! restarts during local development. Each binding gets its own table
+//! (`kv_<name>` or `storage_<name>`), created on first use.
+//!
+//! Database bindings get their own SQLite database instead (in memory
+//! unless a file is configured with `with_database_file`), see
+//! [`database`] for the query semantics.
+//!
+//! KV semantics are the same as [`crate::MemoryKv`]. Storage semantics:
+//! - `Get`, `GetStream`, `Head` and `Copy` of a missing key return `NotFound`
+//! - `PutStream` buffers the body (objects are stored as a single blob)
+//! - Object metadata (`PutOptions`) is stored as JSON next to the body
+//! - Ranged reads only load the requested bytes; conditions are evaluated
+//!   against the stored metadata before the body is read
+//! - `Fetch` of a missing key returns a 404 response
+//! - `List` returns keys in lexicographic (byte) order, `truncated` is set
+//!   when more keys or delimited prefixes match than `limit` (default and
+//!   max: 1000), with a `cursor` to resume from
+//! - Puts verify the supplied `checksums` and fail on mismatch; multipart
+//!   uploads do not accept checksums
+//! - `Copy` duplicates the row (body, etag and metadata) under the new key
+//! - `Delete` of missing keys succeeds, batches run in one transaction
+//! - `Presign` is not supported (there is no URL to sign)
+//! - Multipart uploads keep their parts in a `multipart_<name>` table until
+//!   completed or aborted; completion copies the parts into the object row
+//!   with incremental blob I/O, and the etag is `<hash of part etags>-<count>`
+//!
+//! Queries run synchronously on the calling thread; this backend is meant
+//! for single-process development runners, not production traffic.
+
+mod database;
+
+use super::{
+    ListPage, check_checksums, check_completed_parts, check_delete_keys, check_multipart_options,
+    check_part_number, check_part_size, etag, list_page, multipart_etag, new_upload_id,
+    object_response, rejection_response, rejection_result,
+};
+use crate::{
+    ContentRange, DatabaseOp, DatabaseResult, HttpResponse, KvLimits, KvOp, KvResult, ListOptions,
+    ObjectMetadata, OpFuture, OperationsHandler, PutOptions, QueryLimits, ResponseBody,
+    StorageObject, StorageOp, StorageResult, StorageStream, UploadedPart,
+};
+use bytes::Bytes;
+use rusqlite::types::Type;
+use rusqlite::{Connection, MAIN_DB, OptionalExtension, params};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of backends created, for unique in-memory database names
+static INSTANCES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum TableKind {
+    Kv,
+    Storage,
+    /// Pending multipart uploads (part 0 holds the upload itself)
+    Multipart,
+}
+
+struct Inner {
+    conn: Connection,
+    /// Tables already created in this process
+    tables: HashSet<(TableKind, String)>,
+}
+
+/// SQLite-backed store implementing `handle_binding_kv`, `handle_binding_storage`
+/// and `handle_binding_database`
+///
+/// # Example
+///
+/// ```ignore
+/// let backend = SqliteBackend::open(".openworkers/state.db")?;
+/// let ops: OperationsHandle = Arc::new(backend);
+/// ```
+pub struct SqliteBackend {
+    kv_limits: KvLimits,
+    query_limits: QueryLimits,
+    inner: Mutex<Inner>,
+    /// Unique id of this backend, naming its in-memory databases
+    instance: u64,
+    /// Database files of database bindings (others are in memory)
+    database_files: HashMap<String, PathBuf>,
+    /// Open connections of database bindings
+    databases: Mutex<HashMap<String, Connection>>,
+}
+
+impl std::fmt::Debug for SqliteBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteBackend")
+            .field("kv_limits", &self.kv_limits)
+            .field("query_limits", &self.query_limits)
+            .field("database_files", &self.database_files)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqliteBackend {
+    /// Open (or create) a database file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| e.to_string())?;
+        Ok(Self::from_connection(conn))
+    }
+
+    /// Open a transient in-memory database
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+        Ok(Self::from_connection(conn))
+    }
+
+    /// Wrap an existing connection
+    pub fn from_connection(conn: Connection) -> Self {
+        Self {
+            kv_limits: KvLimits::default(),
+            query_limits: QueryLimits::default(),
+            inner: Mutex::new(Inner {
+                conn,
+                tables: HashSet::new(),
+            }),
+            instance: INSTANCES.fetch_add(1, Ordering::Relaxed),
+            database_files: HashMap::new(),
+            databases: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Store a database binding in a file (opened or created on first use)
+    pub fn with_database_file(mut self, binding: &str, path: impl Into<PathBuf>) -> Self {
+        self.database_files.insert(binding.to_string(), path.into());
+        self
+    }
+
+    /// Set the limits enforced on KV operations
+    pub fn with_kv_limits(mut self, limits: KvLimits) -> Self {
+        self.kv_limits = limits;
+        self
+    }
+
+    /// Set the limits enforced on streamed query results
+    /// (`RuntimeLimits::query_limits`)
+    pub fn with_query_limits(mut self, limits: QueryLimits) -> Self {
+        self.query_limits = limits;
+        self
+    }
+
+    /// Execute a KV operation against a binding
+    pub fn execute_kv(&self, binding: &str, op: KvOp) -> KvResult {
+        match self.try_execute_kv(binding, op) {
+            Ok(result) => result,
+            Err(err) => KvResult::Error(err),
+        }
+    }
+
+    /// Execute a storage operation against a binding
+    pub async fn execute_storage(&self, binding: &str, op: StorageOp) -> StorageResult {
+        // Objects are stored as a single blob, so streamed bodies are buffered
+        let op = match op {
+            StorageOp::PutStream { key, body, options } => match body.collect().await {
+                Ok(body) => StorageOp::Put { key, body, options },
+                Err(err) => return StorageResult::Error(err),
+            },
+            op => op,
+        };
+
+        match self.try_execute_storage(binding, op) {
+            Ok(result) => result,
+            Err(err) => StorageResult::Error(err),
+        }
+    }
+
+    /// Execute a database operation against a binding
+    pub fn execute_database(&self, binding: &str, op: DatabaseOp) -> DatabaseResult {
+        let mut databases = self.databases.lock().unwrap();
+
+        // The first connection of a binding also keeps in-memory databases alive
+        let conn = match databases.entry(binding.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match self.open_database(binding) {
+                Ok(conn) => entry.insert(conn),
+                Err(err) => return DatabaseResult::Error(err.to_string().into()),
+            },
+        };
+
+        match op {
+            // Streams read from their own connection, on their own thread
+            DatabaseOp::QueryStream {
+                sql,
+                params,
+                batch_size,
+            } => match self.open_database(binding) {
+                Ok(conn) => database::stream(conn, sql, params, batch_size, self.query_limits),
+                Err(err) => DatabaseResult::Error(err.to_string().into()),
+            },
+            op => database::execute(conn, op),
+        }
+    }
+
+    /// Open a new connection to a binding's database
+    ///
+    /// In-memory databases use a shared cache, so that every connection of
+    /// a binding sees the same data.
+    fn open_database(&self, binding: &str) -> rusqlite::Result<Connection> {
+        match self.database_files.get(binding) {
+            Some(path) => Connection::open(path),
+            None => Connection::open(format!(
+                "file:openworkers-{}-{}?mode=memory&cache=shared",
+                self.instance,
+                etag(binding.as_bytes())
+            )),
+        }
+    }
+
+    fn try_execute_kv(&self, binding: &str, op: KvOp) -> Result<KvResult, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let table = inner.table(TableKind::Kv, binding)?;
+        let now = now_ms();
+
+        match op {
+            KvOp::Get { key } => {
+                self.kv_limits.check_key(&key)?;
+
+                let value: Option<String> = inner
+                    .conn
+                    .query_row(
+                        &format!(
+                            "SELECT value FROM {table} WHERE key = ?1 \
+                             AND (expires_at IS NULL OR expires_at > ?2)"
+                        ),
+                        params![key, now],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .map_err(|e| e.to_string())?;
+
+                let value = value
+                    .map(|v| serde_json::from_str(&v))
+                    .transpose()
+                    .map_err(|e| e.to_string())?;
+
+                Ok(KvResult::Value(value))
+            }
+
+            KvOp::Put {
+                key,
+                value,
+                expires_in,
+            } => {
+                self.kv_limits.check_key(&key)?;
+                self.kv_limits.check_value(&value)?;
+
+                let expires_at = self
+                    .kv_limits
+                    .check_expires_in(expires_in)?
+                    .and_then(|ttl| now.checked_add(ttl.as_millis().try_into().ok()?));
+
+                inner
+                    .conn
+                    .execute(
+                        &format!(
+                            "INSERT OR REPLACE INTO {table} (key, value, expires_at) \
+                             VALUES (?1, ?2, ?3)"
+                        ),
+                        params![key, value.to_string(), expires_at],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                Ok(KvResult::Ok)
+            }
+
+            KvOp::Delete { key } => {
+                self.kv_limits.check_key(&key)?;
+
+                inner
+                    .conn
+                    .execute(&format!("DELETE FROM {table} WHERE key = ?1"), params![key])
+                    .map_err(|e| e.to_string())?;
+
+                Ok(KvResult::Ok)
+            }
+
+            KvOp::List {
+                prefix,
+                limit,
+                cursor,
+            } => {
+                inner
+                    .conn
+                    .execute(
+                        &format!("DELETE FROM {table} WHERE expires_at <= ?1"),
+                        params![now],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                let limit = self.kv_limits.list_limit(limit);
+                let keys = inner.list_keys(&table, prefix.as_deref(), cursor.as_deref(), limit)?;
+
+                Ok(KvResult::Keys(keys))
+            }
+        }
+    }
+
+    fn try_execute_storage(&self, binding: &str, op: StorageOp) -> Result<StorageResult, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let table = inner.table(TableKind::Storage, binding)?;
+
+        match op {
+            StorageOp::Get { key, options } => {
+                let Some(metadata) = inner.metadata(&table, &key)? else {
+                    return Ok(StorageResult::NotFound);
+                };
+
+                let range = match options.evaluate(&metadata) {
+                    Ok(range) => range,
+                    Err(rejection) => return Ok(rejection_result(metadata, rejection)),
+                };
+
+                let body = inner.body(&table, &key, range)?;
+
+                Ok(StorageResult::Object(StorageObject {
+                    metadata,
+                    range,
+                    body,
+                }))
+            }
+
+            StorageOp::GetStream { key, options } => {
+                let Some(metadata) = inner.metadata(&table, &key)? else {
+                    return Ok(StorageResult::NotFound);
+                };
+
+                let range = match options.evaluate(&metadata) {
+                    Ok(range) => range,
+                    Err(rejection) => return Ok(rejection_result(metadata, rejection)),
+                };
+
+                let body = inner.body(&table, &key, range)?;
+
+                Ok(StorageResult::Stream(StorageObject {
+                    metadata,
+                    range,
+                    body: StorageStream::from_bytes(Bytes::from(body)),
+                }))
+            }
+
+            StorageOp::Fetch { key, options } => {
+                let Some(metadata) = inner.metadata(&table, &key)? else {
+                    return Ok(StorageResult::Response(HttpResponse {
+                        status: 404,
+                        headers: vec![],
+                        body: ResponseBody::None,
+                    }));
+                };
+
+                let response = match options.evaluate(&metadata) {
+                    Ok(range) => {
+                        let body = inner.body(&table, &key, range)?;
+                        object_response(&metadata, range, ResponseBody::Bytes(Bytes::from(body)))
+                    }
+                    Err(rejection) => rejection_response(&metadata, rejection),
+                };
+
+                Ok(StorageResult::Response(response))
+            }
+
+            StorageOp::Put { key, body, options } => {
+                check_checksums(&options.checksums, &body)?;
+
+                let etag = etag(&body);
+                inner.put(&table, &key, &body, &etag, &options)?;
+
+                Ok(StorageResult::metadata(inner.metadata(&table, &key)?))
+            }
+
+            StorageOp::PutStream { .. } => {
+                Err("Streaming put must be buffered before execution".into())
+            }
+
+            StorageOp::Head { key } => Ok(StorageResult::metadata(inner.metadata(&table, &key)?)),
+
+            StorageOp::List { options } => {
+                let page = inner.list_page(&table, &options)?;
+                let mut objects = Vec::with_capacity(page.keys.len());
+
+                for key in page.keys {
+                    if let Some(metadata) = inner.metadata(&table, &key)? {
+                        objects.push(options.filter_metadata(metadata));
+                    }
+                }
+
+                Ok(StorageResult::List {
+                    objects,
+                    delimited_prefixes: page.delimited_prefixes,
+                    truncated: page.truncated,
+                    cursor: page.cursor,
+                })
+            }
+
+            StorageOp::Copy { from, to } => {
+                let copied = inner
+                    .conn
+                    .execute(
+                        &format!(
+                            "INSERT OR REPLACE INTO {table} (key, body, etag, uploaded_at, metadata) \
+                             SELECT ?2, body, etag, ?3, metadata FROM {table} WHERE key = ?1"
+                        ),
+                        params![from, to, now_ms()],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                if copied == 0 {
+                    return Ok(StorageResult::NotFound);
+                }
+
+                Ok(StorageResult::metadata(inner.metadata(&table, &to)?))
+            }
+
+            StorageOp::Delete { keys } => {
+                check_delete_keys(&keys)?;
+
+                let tx = inner
+                    .conn
+                    .unchecked_transaction()
+                    .map_err(|e| e.to_string())?;
+
+                for key in &keys {
+                    tx.execute(&format!("DELETE FROM {table} WHERE key = ?1"), params![key])
+                        .map_err(|e| e.to_string())?;
+                }
+
+                tx.commit().map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::Body(None))
+            }
+
+            StorageOp::Presign { .. } => {
+                Err("Presigned URLs are not supported by the SQLite backend".into())
+            }
+
+            StorageOp::CreateMultipartUpload { key, options } => {
+                check_multipart_options(&options)?;
+
+                let uploads = inner.table(TableKind::Multipart, binding)?;
+                let upload_id = new_upload_id();
+                let options = serde_json::to_string(&options).map_err(|e| e.to_string())?;
+
+                inner
+                    .conn
+                    .execute(
+                        &format!(
+                            "INSERT INTO {uploads} (upload_id, part_number, key, metadata) \
+                             VALUES (?1, 0, ?2, ?3)"
+                        ),
+                        params![upload_id, key, options],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::MultipartUpload { key, upload_id })
+            }
+
+            StorageOp::UploadPart {
+                key,
+                upload_id,
+                part_number,
+                body,
+            } => {
+                check_part_number(part_number)?;
+
+                let uploads = inner.table(TableKind::Multipart, binding)?;
+                inner.upload_options(&uploads, &key, &upload_id)?;
+
+                let etag = etag(&body);
+
+                inner
+                    .conn
+                    .execute(
+                        &format!(
+                            "INSERT OR REPLACE INTO {uploads} \
+                             (upload_id, part_number, key, etag, body) \
+                             VALUES (?1, ?2, ?3, ?4, ?5)"
+                        ),
+                        params![upload_id, part_number, key, etag, body],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::UploadedPart(UploadedPart {
+                    part_number,
+                    etag,
+                }))
+            }
+
+            StorageOp::CompleteMultipartUpload {
+                key,
+                upload_id,
+                parts,
+            } => {
+                check_completed_parts(&parts)?;
+
+                let uploads = inner.table(TableKind::Multipart, binding)?;
+                let options = inner.upload_options(&uploads, &key, &upload_id)?;
+
+                let tx = inner
+                    .conn
+                    .unchecked_transaction()
+                    .map_err(|e| e.to_string())?;
+
+                // Row ids and sizes of the parts, in order
+                let mut sources = Vec::with_capacity(parts.len());
+
+                for (index, part) in parts.iter().enumerate() {
+                    let stored: Option<(i64, String, u64)> = tx
+                        .query_row(
+                            &format!(
+                                "SELECT rowid, etag, length(body) FROM {uploads} \
+                                 WHERE upload_id = ?1 AND part_number = ?2"
+                            ),
+                            params![upload_id, part.part_number],
+                            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                        )
+                        .optional()
+                        .map_err(|e| e.to_string())?;
+
+                    let Some((rowid, _, size)) = stored.filter(|(_, etag, _)| *etag == part.etag)
+                    else {
+                        return Err(format!("Invalid part {}", part.part_number));
+                    };
+
+                    check_part_size(part.part_number, size, index + 1 == parts.len())?;
+                    sources.push((rowid, size));
+                }
+
+                // The object is written in place, one part at a time, instead
+                // of being assembled in memory
+                let size: u64 = sources.iter().map(|(_, size)| size).sum();
+                let options = serde_json::to_string(&options).map_err(|e| e.to_string())?;
+
+                tx.execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO {table} (key, body, etag, uploaded_at, metadata) \
+                         VALUES (?1, zeroblob(?2), ?3, ?4, ?5)"
+                    ),
+                    params![key, size, multipart_etag(&parts), now_ms(), options],
+                )
+                .map_err(|e| e.to_string())?;
+
+                let mut object = tx
+                    .blob_open(
+                        MAIN_DB,
+                        table_name(TableKind::Storage, binding).as_str(),
+                        "body",
+                        tx.last_insert_rowid(),
+                        false,
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                for (rowid, _) in sources {
+                    let mut part = tx
+                        .blob_open(
+                            MAIN_DB,
+                            table_name(TableKind::Multipart, binding).as_str(),
+                            "body",
+                            rowid,
+                            true,
+                        )
+                        .map_err(|e| e.to_string())?;
+
+                    std::io::copy(&mut part, &mut object).map_err(|e| e.to_string())?;
+                }
+
+                drop(object);
+
+                tx.execute(
+                    &format!("DELETE FROM {uploads} WHERE upload_id = ?1"),
+                    params![upload_id],
+                )
+                .map_err(|e| e.to_string())?;
+
+                tx.commit().map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::metadata(inner.metadata(&table, &key)?))
+            }
+
+            StorageOp::AbortMultipartUpload { key, upload_id } => {
+                let uploads = inner.table(TableKind::Multipart, binding)?;
+                inner.upload_options(&uploads, &key, &upload_id)?;
+
+                inner
+                    .conn
+                    .execute(
+                        &format!("DELETE FROM {uploads} WHERE upload_id = ?1"),
+                        params![upload_id],
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                Ok(StorageResult::Body(None))
+            }
+        }
+    }
+}
+
+/// Unquoted table name for a binding
+fn table_name(kind: TableKind, binding: &str) -> String {
+    match kind {
+        TableKind::Kv => format!("kv_{}", binding),
+        TableKind::Storage => format!("storage_{}", binding),
+        TableKind::Multipart => format!("multipart_{}", binding),
+    }
+}
+
+impl Inner {
+    /// Quoted table name for a binding, creating the table if needed
+    fn table(&mut self, kind: TableKind, binding: &str) -> Result<String, String> {
+        let name = table_name(kind, binding);
+        let quoted = format!("\"{}\"", name.replace('"', "\"\""));
+
+        if self.tables.insert((kind, binding.to_string())) {
+            let schema = match kind {
+                TableKind::Kv => {
+                    "key TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL, expires_at INTEGER"
+                }
+                TableKind::Storage => {
+                    "key TEXT PRIMARY KEY NOT NULL, body BLOB NOT NULL, etag TEXT NOT NULL, \
+                     uploaded_at INTEGER NOT NULL, metadata TEXT"
+                }
+                TableKind::Multipart => {
+                    "upload_id TEXT NOT NULL, part_number INTEGER NOT NULL, key TEXT NOT NULL, \
+                     etag TEXT, body BLOB, metadata TEXT, PRIMARY KEY (upload_id, part_number)"
+                }
+            };
+
+            let created = self
+                .conn
+                .execute(
+                    &format!("CREATE TABLE IF NOT EXISTS {quoted} ({schema})"),
+                    [],
+                )
+                .and_then(|_| match kind {
+                    TableKind::Storage => self.add_column(&quoted, "metadata", "TEXT"),
+                    TableKind::Kv | TableKind::Multipart => Ok(()),
+                })
+                .map_err(|e| e.to_string());
+
+            if let Err(err) = created {
+                self.tables.remove(&(kind, binding.to_string()));
+                return Err(err);
+            }
+        }
+
+        Ok(quoted)
+    }
+
+    /// Add a column to a table created by an older version, if missing
+    fn add_column(&self, table: &str, column: &str, sql_type: &str) -> rusqlite::Result<()> {
+        let exists = self
+            .conn
+            .prepare(&format!(
+                "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+                {
+                    // pragma_table_info takes the unquoted name as a string literal
+                    table
+                        .trim_matches('"')
+                        .replace("\"\"", "\"")
+                        .replace('\'', "''")
+                }
+            ))?
+            .exists(params![column])?;
+
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {sql_type}"),
+                [],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Insert or replace an object
+    fn put(
+        &self,
+        table: &str,
+        key: &str,
+        body: &[u8],
+        etag: &str,
+        options: &PutOptions,
+    ) -> Result<(), String> {
+        let options = serde_json::to_string(options).map_err(|e| e.to_string())?;
+
+        self.conn
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {table} (key, body, etag, uploaded_at, metadata) \
+                     VALUES (?1, ?2, ?3, ?4, ?5)"
+                ),
+                params![key, body, etag, now_ms(), options],
+            )
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Options of a pending multipart upload for `key`
+    fn upload_options(
+        &self,
+        uploads: &str,
+        key: &str,
+        upload_id: &str,
+    ) -> Result<PutOptions, String> {
+        let options: Option<Option<String>> = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT metadata FROM {uploads} \
+                     WHERE upload_id = ?1 AND part_number = 0 AND key = ?2"
+                ),
+                params![upload_id, key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        let Some(options) = options else {
+            return Err(format!("Multipart upload '{}' not found", upload_id));
+        };
+
+        options
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map(Option::unwrap_or_default)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Metadata of a stored object
+    fn metadata(&self, table: &str, key: &str) -> Result<Option<ObjectMetadata>, String> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT length(body), etag, uploaded_at, metadata FROM {table} WHERE key = ?1"
+                ),
+                params![key],
+                |row| object_metadata(key, row),
+            )
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Body of a stored object, or a byte range of it
+    fn body(&self, table: &str, key: &str, range: Option<ContentRange>) -> Result<Vec<u8>, String> {
+        // substr() works on bytes for BLOBs, with 1-based offsets
+        let (offset, length) = match range {
+            Some(range) => (range.offset as i64 + 1, range.length as i64),
+            None => (1, i64::MAX),
+        };
+
+        self.conn
+            .query_row(
+                &format!("SELECT substr(body, ?2, ?3) FROM {table} WHERE key = ?1"),
+                params![key, offset, length],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    /// Keys starting with `prefix`, in lexicographic order
+    fn list_keys(
+        &self,
+        table: &str,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<String>, String> {
+        let prefix = prefix.unwrap_or_default();
+        // Keys are never empty: an empty cursor excludes nothing
+        let cursor = cursor.unwrap_or_default();
+
+        // BINARY collation compares UTF-8 bytes, which matches Rust string ordering
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT key FROM {table} WHERE key >= ?1 AND key > ?3 \
+                 AND substr(key, 1, length(?1)) = ?1 ORDER BY key LIMIT ?2"
+            ))
+            .map_err(|e| e.to_string())?;
+
+        stmt.query_map(params![prefix, limit, cursor], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| e.to_string())
+    }
+
+    /// One page of a storage listing
+    fn list_page(&self, table: &str, options: &ListOptions) -> Result<ListPage, String> {
+        let prefix = options.prefix.as_deref().unwrap_or_default();
+        let cursor = options.cursor.as_deref().unwrap_or_default();
+
+        // Keys are read lazily: grouped prefixes may span many rows
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT key FROM {table} WHERE key >= ?1 AND key > ?2 \
+                 AND substr(key, 1, length(?1)) = ?1 ORDER BY key"
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let keys = stmt
+            .query_map(params![prefix, cursor], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .map(|key| key.map_err(|e| e.to_string()));
+
+        list_page(keys, options)
+    }
+}
+
+impl OperationsHandler for SqliteBackend {
+    fn handle_binding_kv(&self, binding: &str, op: KvOp) -> OpFuture<'_, KvResult> {
+        let result = self.execute_kv(binding, op);
+        Box::pin(async move { result })
+    }
+
+    fn handle_binding_storage(&self, binding: &str, op: StorageOp) -> OpFuture<'_, StorageResult> {
+        let binding = binding.to_string();
+        Box::pin(async move { self.execute_storage(&binding, op).await })
+    }
+
+    fn handle_binding_database(
+        &self,
+        binding: &str,
+        op: DatabaseOp,
+    ) -> OpFuture<'_, DatabaseResult> {
+        let result = self.execute_database(binding, op);
+        Box::pin(async move { result })
+    }
+}
+
+/// Build object metadata from `size, etag, uploaded_at, metadata` columns
+fn object_metadata(key: &str, row: &rusqlite::Row<'_>) -> rusqlite::Result<ObjectMetadata> {
+    let options: Option<String> = row.get(3)?;
+
+    // Rows written before metadata support have no options
+    let options: PutOptions = options
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, Type::Text, Box::new(e)))?
+        .unwrap_or_default();
+
+    Ok(ObjectMetadata {
+        key: key.to_string(),
+        size: row.get(0)?,
+        etag: row.get(1)?,
+        uploaded: row.get(2)?,
+        http_metadata: options.http_metadata,
+        custom_metadata: options.custom_metadata,
+        checksums: options.checksums,
+    })
+}
+
+/// Current Unix time in milliseconds
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv_put(backend: &SqliteBackend, key: &str, expires_in: Option<u64>) {
+        let op = KvOp::Put {
+            key: key.into(),
+            value: serde_json::json!(key),
+            expires_in,
+        };
+        assert!(matches!(backend.execute_kv("KV", op), KvResult::Ok));
+    }
+
+    fn kv_get(backend: &SqliteBackend, key: &str) -> Option<serde_json::Value> {
+        match backend.execute_kv("KV", KvOp::Get { key: key.into() }) {
+            KvResult::Value(value) => value,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    fn kv_list(
+        backend: &SqliteBackend,
+        prefix: Option<&str>,
+        limit: u32,
+        cursor: Option<&str>,
+    ) -> Vec<String> {
+        let op = KvOp::List {
+            prefix: prefix.map(Into::into),
+            limit: Some(limit),
+            cursor: cursor.map(Into::into),
+        };
+
+        match backend.execute_kv("KV", op) {
+            KvResult::Keys(keys) => keys,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn kv_expired_keys_are_missing() {
+        let backend = SqliteBackend::open_in_memory()
+            .unwrap()
+            .with_kv_limits(KvLimits {
+                min_expiration_ttl: 0,
+                ..KvLimits::default()
+            });
+
+        kv_put(&backend, "expired", Some(0));
+        kv_put(&backend, "live", Some(3600));
+        kv_put(&backend, "forever", None);
+
+        assert_eq!(kv_get(&backend, "expired"), None);
+        assert_eq!(kv_get(&backend, "live"), Some(serde_json::json!("live")));
+        assert_eq!(kv_list(&backend, None, 10, None), ["forever", "live"]);
+    }
+
+    #[test]
+    fn kv_list_pages_through_prefix() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+
+        for key in ["a", "b/1", "b/2", "b/3", "b/4", "b/5", "c"] {
+            kv_put(&backend, key, None);
+        }
+
+        assert_eq!(kv_list(&backend, Some("b/"), 2, None), ["b/1", "b/2"]);
+        assert_eq!(
+            kv_list(&backend, Some("b/"), 2, Some("b/2")),
+            ["b/3", "b/4"]
+        );
+        assert_eq!(kv_list(&backend, Some("b/"), 2, Some("b/4")), ["b/5"]);
+        assert!(kv_list(&backend, Some("b/"), 2, Some("b/5")).is_empty());
+        assert_eq!(kv_list(&backend, None, 3, Some("b/5")), ["c"]);
+    }
+
+    #[tokio::test]
+    async fn storage_objects() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+
+        crate::backends::tests::storage_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn list_objects() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+
+        crate::backends::tests::list_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn copy_and_delete_objects() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+
+        crate::backends::tests::copy_delete_scenario(&backend).await;
+    }
+
+    #[tokio::test]
+    async fn multipart_upload() {
+        let backend = SqliteBackend::open_in_memory().unwrap();
+
+        crate::backends::tests::multipart_scenario(&backend).await;
+    }
+}
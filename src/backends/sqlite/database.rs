@@ -0,0 +1,557 @@
+//! Database bindings on embedded SQLite
+//!
+//! Runs PostgreSQL-style queries against one SQLite database per binding:
+//! - `$n` placeholders become `?n` (outside of literals and comments);
+//!   casts (`$1::int`) and other PostgreSQL syntax are not translated
+//! - Parameters bind by type: booleans as 0/1, timestamps as RFC 3339 text,
+//!   JSON values and arrays as JSON text (use `json_each(?1)` for `ANY`)
+//! - Columns declared `BOOLEAN` read back as booleans, `JSON`/`JSONB` as JSON
+//! - `rows_affected` counts returned rows for queries, changed rows otherwise
+//! - Constraint errors carry the equivalent SQLSTATE (`23505` for unique)
+//! - Streamed queries run on a background thread with their own connection,
+//!   and stop with an error at the first row over the `QueryLimits`;
+//!   SQLite locks may make concurrent writes fail until the stream is drained
+//!   or dropped
+
+use crate::database::rfc3339;
+use crate::{
+    DatabaseError, DatabaseErrorKind, DatabaseOp, DatabaseResult, QUERY_STREAM_BATCH_SIZE,
+    QueryLimits, QueryResult, QueryStream, SqlColumn, SqlParam, SqlStatement, SqlValue,
+};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, Row, Statement};
+
+/// Channel capacity (in batches) for streamed queries
+const STREAM_BUFFER_SIZE: usize = 4;
+
+/// Execute a database operation on a binding's connection
+pub(super) fn execute(conn: &Connection, op: DatabaseOp) -> DatabaseResult {
+    let result = match op {
+        DatabaseOp::Query { sql, params } => query(conn, &sql, &params).map(DatabaseResult::Query),
+        DatabaseOp::Batch {
+            statements,
+            transactional,
+        } => batch(conn, &statements, transactional).map(DatabaseResult::Batch),
+        DatabaseOp::QueryStream { .. } => Err("Streamed queries need their own connection".into()),
+    };
+
+    result.unwrap_or_else(DatabaseResult::Error)
+}
+
+/// Stream the rows of a query, read from `conn` on a background thread
+pub(super) fn stream(
+    conn: Connection,
+    sql: String,
+    params: Vec<SqlParam>,
+    batch_size: Option<u32>,
+    limits: QueryLimits,
+) -> DatabaseResult {
+    let batch_size = batch_size.unwrap_or(QUERY_STREAM_BATCH_SIZE).max(1) as usize;
+    let (columns_tx, columns_rx) = std::sync::mpsc::sync_channel(1);
+
+    std::thread::spawn(move || {
+        let mut stmt = match prepare(&conn, &sql, &params) {
+            Ok(prepared) => prepared,
+            Err(err) => {
+                let _ = columns_tx.send(Err(err));
+                return;
+            }
+        };
+
+        let columns = columns(&stmt);
+        let (tx, stream) = QueryStream::channel(columns.clone(), STREAM_BUFFER_SIZE);
+
+        if columns_tx.send(Ok(stream)).is_err() {
+            return;
+        }
+
+        let values = params.iter().map(|param| bind_value(param.to_value()));
+        let mut rows = match stmt.query(rusqlite::params_from_iter(values)) {
+            Ok(rows) => rows,
+            Err(err) => {
+                let _ = tx.blocking_send(Err(database_error(err)));
+                return;
+            }
+        };
+
+        let mut batch = Vec::with_capacity(batch_size);
+        let (mut read_rows, mut read_bytes) = (0, 0);
+
+        loop {
+            let row = match rows.next() {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(err) => {
+                    let _ = tx.blocking_send(Err(database_error(err)));
+                    return;
+                }
+            };
+
+            let values = match read_row(&columns, row) {
+                Ok(values) => values,
+                Err(err) => {
+                    let _ = tx.blocking_send(Err(database_error(err)));
+                    return;
+                }
+            };
+
+            read_rows += 1;
+            read_bytes += values
+                .iter()
+                .map(|value| value.byte_size() as u64)
+                .sum::<u64>();
+
+            // Rows read within the limit are delivered before the error
+            if let Err(message) = limits.check(read_rows, read_bytes) {
+                if batch.is_empty() || tx.blocking_send(Ok(batch)).is_ok() {
+                    let _ = tx.blocking_send(Err(message.into()));
+                }
+                return;
+            }
+
+            batch.push(values);
+
+            // Stops reading as soon as the stream is dropped
+            if batch.len() == batch_size
+                && tx
+                    .blocking_send(Ok(std::mem::replace(
+                        &mut batch,
+                        Vec::with_capacity(batch_size),
+                    )))
+                    .is_err()
+            {
+                return;
+            }
+        }
+
+        if !batch.is_empty() {
+            let _ = tx.blocking_send(Ok(batch));
+        }
+    });
+
+    match columns_rx.recv() {
+        Ok(Ok(stream)) => DatabaseResult::Stream(stream),
+        Ok(Err(err)) => DatabaseResult::Error(err),
+        Err(_) => DatabaseResult::Error("Query stream thread failed".into()),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn batch(
+    conn: &Connection,
+    statements: &[SqlStatement],
+    transactional: bool,
+) -> Result<Vec<QueryResult>, DatabaseError> {
+    // Rolled back on drop unless committed
+    let tx = match transactional {
+        true => Some(conn.unchecked_transaction().map_err(database_error)?),
+        false => None,
+    };
+
+    let mut results = Vec::with_capacity(statements.len());
+
+    for (index, statement) in statements.iter().enumerate() {
+        let result = query(conn, &statement.sql, &statement.params).map_err(|mut err| {
+            err.message = format!("Statement {}: {}", index, err.message);
+            err
+        })?;
+
+        results.push(result);
+    }
+
+    if let Some(tx) = tx {
+        tx.commit().map_err(database_error)?;
+    }
+
+    Ok(results)
+}
+
+#[allow(clippy::result_large_err)]
+fn query(conn: &Connection, sql: &str, params: &[SqlParam]) -> Result<QueryResult, DatabaseError> {
+    let mut stmt = prepare(conn, sql, params)?;
+    let columns = columns(&stmt);
+    let total_changes = conn.total_changes();
+
+    let values = params.iter().map(|param| bind_value(param.to_value()));
+    let mut rows = stmt
+        .query(rusqlite::params_from_iter(values))
+        .map_err(database_error)?;
+    let mut result = Vec::new();
+
+    while let Some(row) = rows.next().map_err(database_error)? {
+        result.push(read_row(&columns, row).map_err(database_error)?);
+    }
+
+    drop(rows);
+
+    // `changes()` keeps the count of the last INSERT, UPDATE or DELETE, so
+    // statements that changed nothing (DDL, ...) report 0 instead
+    let rows_affected = match columns.is_empty() {
+        true if stmt.readonly() || conn.total_changes() == total_changes => 0,
+        true => conn.changes(),
+        false => result.len() as u64,
+    };
+
+    Ok(QueryResult {
+        columns,
+        rows: result,
+        rows_affected,
+        command: command_tag(sql),
+    })
+}
+
+/// Prepare a statement, checking the number of parameters
+#[allow(clippy::result_large_err)]
+fn prepare<'conn>(
+    conn: &'conn Connection,
+    sql: &str,
+    params: &[SqlParam],
+) -> Result<Statement<'conn>, DatabaseError> {
+    let stmt = conn
+        .prepare(&translate_placeholders(sql))
+        .map_err(database_error)?;
+
+    if stmt.parameter_count() != params.len() {
+        return Err(DatabaseError::new(format!(
+            "Expected {} parameters, got {}",
+            stmt.parameter_count(),
+            params.len()
+        )));
+    }
+
+    Ok(stmt)
+}
+
+/// Result columns of a prepared statement
+fn columns(stmt: &Statement<'_>) -> Vec<SqlColumn> {
+    stmt.columns()
+        .iter()
+        .map(|column| SqlColumn {
+            name: column.name().to_string(),
+            type_name: column.decl_type().unwrap_or_default().to_string(),
+            type_oid: None,
+        })
+        .collect()
+}
+
+/// Typed values of a result row
+fn read_row(columns: &[SqlColumn], row: &Row<'_>) -> rusqlite::Result<Vec<SqlValue>> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| row.get_ref(index).map(|value| read_value(column, value)))
+        .collect()
+}
+
+/// Replace `$n` placeholders with SQLite's `?n`
+fn translate_placeholders(sql: &str) -> String {
+    let mut translated = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        translated.push(c);
+
+        match c {
+            // Literals and quoted identifiers: copy up to the closing quote
+            // (doubled quotes close and reopen, which copies them unchanged)
+            '\'' | '"' | '`' => {
+                for next in chars.by_ref() {
+                    translated.push(next);
+
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    translated.push(next);
+
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                let mut previous = '\0';
+
+                for next in chars.by_ref() {
+                    translated.push(next);
+
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+
+                    previous = next;
+                }
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                translated.pop();
+                translated.push('?');
+            }
+            _ => {}
+        }
+    }
+
+    translated
+}
+
+/// SQLite value to bind for a parameter
+fn bind_value(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Bool(b) => Value::Integer(b.into()),
+        SqlValue::Int(i) => Value::Integer(i),
+        SqlValue::Float(f) => Value::Real(f),
+        SqlValue::Numeric(s) | SqlValue::Text(s) | SqlValue::Uuid(s) => Value::Text(s),
+        SqlValue::Bytes(bytes) => Value::Blob(bytes),
+        SqlValue::Json(json) => Value::Text(json.to_string()),
+        SqlValue::Timestamp(micros) => Value::Text(rfc3339(micros)),
+        array @ SqlValue::Array(_) => Value::Text(array.to_json().to_string()),
+    }
+}
+
+/// Typed value of a result cell, using the declared column type as a hint
+fn read_value(column: &SqlColumn, value: ValueRef<'_>) -> SqlValue {
+    let declared = column.type_name.to_ascii_uppercase();
+
+    match value {
+        ValueRef::Null => SqlValue::Null,
+        ValueRef::Integer(i) if matches!(declared.as_str(), "BOOL" | "BOOLEAN") => {
+            SqlValue::Bool(i != 0)
+        }
+        ValueRef::Integer(i) => SqlValue::Int(i),
+        ValueRef::Real(f) => SqlValue::Float(f),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text).into_owned();
+
+            match declared.as_str() {
+                "JSON" | "JSONB" => serde_json::from_str(&text)
+                    .map(SqlValue::Json)
+                    .unwrap_or(SqlValue::Text(text)),
+                _ => SqlValue::Text(text),
+            }
+        }
+        ValueRef::Blob(bytes) => SqlValue::Bytes(bytes.to_vec()),
+    }
+}
+
+/// Command tag of a statement: its first keyword, uppercased
+fn command_tag(sql: &str) -> String {
+    sql.trim_start()
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase()
+}
+
+/// Database error with the closest SQLSTATE for SQLite failures
+fn database_error(err: rusqlite::Error) -> DatabaseError {
+    use rusqlite::ffi;
+
+    let (failure, message) = match &err {
+        rusqlite::Error::SqliteFailure(failure, message) => (failure, message),
+        // Translation keeps the characters of the statement in place, so
+        // positions in the translated statement match the original one
+        rusqlite::Error::SqlInputError {
+            msg, sql, offset, ..
+        } => {
+            let mut error = DatabaseError::new(msg.clone()).with_code("42601");
+            error.position = usize::try_from(*offset)
+                .ok()
+                .and_then(|offset| sql.get(..offset))
+                .and_then(|prefix| u32::try_from(prefix.chars().count() + 1).ok());
+            return error;
+        }
+        _ => return DatabaseError::new(err.to_string()),
+    };
+
+    let message = message.clone().unwrap_or_else(|| err.to_string());
+    let error = DatabaseError::new(message);
+
+    match failure.extended_code {
+        ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY => {
+            return error.with_code("23505");
+        }
+        ffi::SQLITE_CONSTRAINT_NOTNULL => return error.with_code("23502"),
+        ffi::SQLITE_CONSTRAINT_FOREIGNKEY => return error.with_code("23503"),
+        ffi::SQLITE_CONSTRAINT_CHECK => return error.with_code("23514"),
+        _ => {}
+    }
+
+    match failure.code {
+        ffi::ErrorCode::ConstraintViolation => error.with_code("23000"),
+        ffi::ErrorCode::DatabaseBusy | ffi::ErrorCode::DatabaseLocked => {
+            error.with_kind(DatabaseErrorKind::Timeout)
+        }
+        ffi::ErrorCode::PermissionDenied
+        | ffi::ErrorCode::ReadOnly
+        | ffi::ErrorCode::AuthorizationForStatementDenied => {
+            error.with_kind(DatabaseErrorKind::Permission)
+        }
+        ffi::ErrorCode::TypeMismatch | ffi::ErrorCode::TooBig => {
+            error.with_kind(DatabaseErrorKind::Data)
+        }
+        // SQLITE_ERROR: syntax errors and unknown tables or columns
+        ffi::ErrorCode::Unknown => error.with_kind(DatabaseErrorKind::Syntax),
+        _ => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SqlPrimitive, TypedParam};
+
+    fn text(value: &str) -> SqlParam {
+        SqlParam::Primitive(SqlPrimitive::String(value.into()))
+    }
+
+    fn statement(sql: &str, params: Vec<SqlParam>) -> SqlStatement {
+        SqlStatement {
+            sql: sql.into(),
+            params,
+        }
+    }
+
+    fn connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE users (name TEXT PRIMARY KEY, admin BOOLEAN, meta JSON)")
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn translate_placeholders_skips_literals_and_comments() {
+        assert_eq!(
+            translate_placeholders("SELECT $1, '$2', \"$3\", `$4` -- $5\n/* $6 */ $7"),
+            "SELECT ?1, '$2', \"$3\", `$4` -- $5\n/* $6 */ ?7"
+        );
+        assert_eq!(
+            translate_placeholders("SELECT 'it''s $1' || $2, $ || $a"),
+            "SELECT 'it''s $1' || ?2, $ || $a"
+        );
+    }
+
+    #[test]
+    fn unique_violation_maps_to_sqlstate() {
+        let conn = connection();
+        let insert = "INSERT INTO users (name) VALUES ($1)";
+
+        query(&conn, insert, &[text("alice")]).unwrap();
+        let err = query(&conn, insert, &[text("alice")]).unwrap_err();
+
+        assert_eq!(err.code.as_deref(), Some("23505"));
+        assert_eq!(err.kind, DatabaseErrorKind::ConstraintViolation);
+    }
+
+    #[test]
+    fn syntax_error_position_counts_characters() {
+        let conn = connection();
+        let err = query(&conn, "SELECT 'ééé' ,, 1", &[]).unwrap_err();
+
+        assert_eq!(err.code.as_deref(), Some("42601"));
+        assert_eq!(err.position, Some(15));
+    }
+
+    #[test]
+    fn transactional_batch_rolls_back() {
+        let conn = connection();
+        let insert = "INSERT INTO users (name) VALUES ($1)";
+
+        let err = batch(
+            &conn,
+            &[
+                statement(insert, vec![text("alice")]),
+                statement(insert, vec![text("alice")]),
+            ],
+            true,
+        )
+        .unwrap_err();
+        assert!(err.message.starts_with("Statement 1: "));
+
+        let count = query(&conn, "SELECT name FROM users", &[]).unwrap();
+        assert!(count.rows.is_empty());
+
+        // Without a transaction, the first statement stays applied
+        batch(
+            &conn,
+            &[
+                statement(insert, vec![text("bob")]),
+                statement(insert, vec![text("bob")]),
+            ],
+            false,
+        )
+        .unwrap_err();
+
+        let count = query(&conn, "SELECT name FROM users", &[]).unwrap();
+        assert_eq!(count.rows, vec![vec![SqlValue::Text("bob".into())]]);
+    }
+
+    #[test]
+    fn boolean_and_json_columns_read_back_typed() {
+        let conn = connection();
+        let meta = serde_json::json!({ "tags": ["a", "b"] });
+
+        query(
+            &conn,
+            "INSERT INTO users (name, admin, meta) VALUES ($1, $2, $3)",
+            &[
+                text("alice"),
+                SqlParam::Primitive(SqlPrimitive::Bool(true)),
+                SqlParam::Typed(TypedParam {
+                    value: SqlValue::Json(meta.clone()),
+                    type_name: None,
+                }),
+            ],
+        )
+        .unwrap();
+
+        let result = query(&conn, "SELECT admin, meta FROM users", &[]).unwrap();
+
+        assert_eq!(
+            result.rows,
+            vec![vec![SqlValue::Bool(true), SqlValue::Json(meta)]]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_stops_at_row_limit() {
+        let conn = Connection::open_in_memory().unwrap();
+        let sql = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n LIMIT 1000) \
+                   SELECT x FROM n";
+        let limits = QueryLimits {
+            max_rows: 10,
+            max_bytes: 0,
+        };
+
+        let DatabaseResult::Stream(mut stream) = stream(conn, sql.into(), vec![], Some(4), limits)
+        else {
+            panic!("expected a stream");
+        };
+
+        // All 10 rows within the limit, then the error instead of the 11th
+        for size in [4, 4, 2] {
+            assert_eq!(stream.next_batch().await.unwrap().unwrap().len(), size);
+        }
+
+        let err = stream.next_batch().await.unwrap().unwrap_err();
+        assert_eq!(err.message, "Query result exceeds 10 rows");
+        assert!(stream.next_batch().await.is_none());
+    }
+
+    #[test]
+    fn statements_without_changes_affect_no_rows() {
+        let conn = connection();
+
+        let insert = query(&conn, "INSERT INTO users (name) VALUES ('a'), ('b')", &[]).unwrap();
+        assert_eq!(insert.rows_affected, 2);
+
+        let create = query(&conn, "CREATE TABLE other (id INTEGER)", &[]).unwrap();
+        assert_eq!(create.rows_affected, 0);
+        assert_eq!(create.command, "CREATE");
+
+        let delete = query(&conn, "DELETE FROM users WHERE name = 'c'", &[]).unwrap();
+        assert_eq!(delete.rows_affected, 0);
+    }
+}
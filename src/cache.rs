@@ -0,0 +1,223 @@
+//! HTTP cache types shared by cache operations
+//!
+//! Caching rules of the Cache API (`caches.default`, `caches.open(name)`):
+//! entries are keyed by request URL, only `GET` requests are cached, and
+//! responses are stored for their shared freshness lifetime (`s-maxage`,
+//! else `max-age`, else `Expires`). Responses varying on request headers
+//! (`Vary`) are stored once per combination of those header values.
+
+use crate::{HttpMethod, HttpRequest, HttpResponse};
+use std::time::SystemTime;
+
+/// Status codes cacheable by default (RFC 9110, section 15.1)
+const CACHEABLE_STATUSES: [u16; 12] = [200, 203, 204, 206, 300, 301, 308, 404, 405, 410, 414, 501];
+
+/// Options for matching and deleting cache entries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheQueryOptions {
+    /// Match any request method, not only `GET`
+    pub ignore_method: bool,
+}
+
+impl CacheQueryOptions {
+    /// Whether a request can match a cache entry, given its method
+    pub fn allows(&self, request: &HttpRequest) -> bool {
+        self.ignore_method || request.method == HttpMethod::Get
+    }
+}
+
+/// Parsed `Cache-Control` response directives relevant to shared caches
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    /// `no-store`: never store the response
+    pub no_store: bool,
+    /// `no-cache`: revalidate before every use (not stored by the Cache API)
+    pub no_cache: bool,
+    /// `private`: only for the end user's cache
+    pub private: bool,
+    /// `max-age` in seconds
+    pub max_age: Option<u64>,
+    /// `s-maxage` in seconds (shared caches, takes precedence over `max-age`)
+    pub s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    /// Parse a `Cache-Control` header value (unknown directives are ignored)
+    pub fn parse(value: &str) -> Self {
+        let mut directives = Self::default();
+
+        for directive in value.split(',') {
+            let (name, arg) = match directive.split_once('=') {
+                Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "private" => directives.private = true,
+                "max-age" => directives.max_age = arg.and_then(|s| s.parse().ok()),
+                "s-maxage" => directives.s_maxage = arg.and_then(|s| s.parse().ok()),
+                _ => {}
+            }
+        }
+
+        directives
+    }
+
+    /// Whether a shared cache may store the response
+    pub fn is_storable(&self) -> bool {
+        !(self.no_store || self.no_cache || self.private)
+    }
+
+    /// Freshness lifetime in seconds for a shared cache, if set by a directive
+    ///
+    /// `max-age=0` gives `Some(0)`: the response is stored but immediately stale.
+    pub fn shared_max_age(&self) -> Option<u64> {
+        self.s_maxage.or(self.max_age)
+    }
+}
+
+/// How a response is stored by a shared cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Freshness lifetime in seconds (None = not stored, 0 = stored but
+    /// immediately stale)
+    pub ttl: Option<u64>,
+    /// Request header names (lowercase) the response varies on
+    pub vary: Vec<String>,
+}
+
+impl CachePolicy {
+    /// Policy for storing `response` as the answer to `request`
+    ///
+    /// Fails for responses the Cache API rejects (non-`GET` requests, partial
+    /// content, `Vary: *`). Responses with `Set-Cookie`, with a status that is
+    /// not cacheable by default or without an explicit freshness lifetime are
+    /// not stored. An invalid `Expires` date means already expired.
+    pub fn for_response(request: &HttpRequest, response: &HttpResponse) -> Result<Self, String> {
+        if request.method != HttpMethod::Get {
+            return Err(format!("Cannot cache {} requests", request.method));
+        }
+
+        if response.status == 206 {
+            return Err("Cannot cache partial responses (206)".into());
+        }
+
+        let vary: Vec<String> = response
+            .headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("vary"))
+            .flat_map(|(_, value)| value.split(','))
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if vary.iter().any(|name| name == "*") {
+            return Err("Cannot cache responses with Vary: *".into());
+        }
+
+        let header = |name: &str| {
+            response
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        let control = header("cache-control")
+            .map(CacheControl::parse)
+            .unwrap_or_default();
+
+        if header("set-cookie").is_some()
+            || !control.is_storable()
+            || !CACHEABLE_STATUSES.contains(&response.status)
+        {
+            return Ok(Self { ttl: None, vary });
+        }
+
+        let ttl = control.shared_max_age().or_else(|| {
+            let Ok(expires) = httpdate::parse_http_date(header("expires")?) else {
+                return Some(0);
+            };
+
+            let date = header("date")
+                .and_then(|date| httpdate::parse_http_date(date).ok())
+                .unwrap_or_else(SystemTime::now);
+
+            Some(expires.duration_since(date).map_or(0, |ttl| ttl.as_secs()))
+        });
+
+        Ok(Self { ttl, vary })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RequestBody, ResponseBody};
+    use std::collections::HashMap;
+
+    fn ttl(status: u16, headers: &[(&str, &str)]) -> Option<u64> {
+        let request = HttpRequest {
+            method: HttpMethod::Get,
+            url: "https://example.com/".into(),
+            headers: HashMap::new(),
+            body: RequestBody::None,
+        };
+        let response = HttpResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: ResponseBody::None,
+        };
+
+        CachePolicy::for_response(&request, &response).unwrap().ttl
+    }
+
+    #[test]
+    fn lifetime_from_directives() {
+        assert_eq!(ttl(200, &[("Cache-Control", "max-age=60")]), Some(60));
+        assert_eq!(
+            ttl(200, &[("Cache-Control", "max-age=60, s-maxage=10")]),
+            Some(10)
+        );
+        assert_eq!(ttl(200, &[("Cache-Control", "max-age=0")]), Some(0));
+        assert_eq!(ttl(200, &[("Cache-Control", "private, max-age=60")]), None);
+        assert_eq!(ttl(200, &[]), None);
+    }
+
+    #[test]
+    fn lifetime_from_expires() {
+        let date = ("Date", "Sun, 06 Nov 1994 08:49:37 GMT");
+
+        assert_eq!(
+            ttl(200, &[date, ("Expires", "Sun, 06 Nov 1994 09:49:37 GMT")]),
+            Some(3600)
+        );
+        assert_eq!(ttl(200, &[date, ("Expires", "0")]), Some(0));
+        assert_eq!(
+            ttl(
+                200,
+                &[
+                    date,
+                    ("Expires", "Sun, 06 Nov 1994 09:49:37 GMT"),
+                    ("Cache-Control", "max-age=5"),
+                ]
+            ),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn only_cacheable_statuses_are_stored() {
+        let max_age = ("Cache-Control", "max-age=60");
+
+        assert_eq!(ttl(404, &[max_age]), Some(60));
+        assert_eq!(ttl(302, &[max_age]), None);
+        assert_eq!(ttl(500, &[max_age]), None);
+        assert_eq!(ttl(200, &[max_age, ("Set-Cookie", "a=b")]), None);
+    }
+}
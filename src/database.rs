@@ -0,0 +1,368 @@
+//! Database result and error types shared by database operations
+//!
+//! Structured query results (`DatabaseResult::Query`): column descriptors
+//! and rows of typed values, so runtimes can build native JS values
+//! (`BigInt`, `Uint8Array`, `Date`) without a JSON text round trip. The
+//! same typed values carry parameters that JSON cannot express (`TypedParam`).
+//!
+//! Large results can be streamed in batches of rows (`QueryStream`).
+//!
+//! Errors keep the SQLSTATE and details reported by the database, with a
+//! stable category (`DatabaseErrorKind`) for retry logic.
+
+use crate::QueryLimits;
+use crate::date::civil_from_days;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Column of a query result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlColumn {
+    /// Column name (or alias)
+    pub name: String,
+    /// Type name as reported by the database (`int8`, `text`, `INTEGER`, ...)
+    pub type_name: String,
+    /// PostgreSQL type OID, if the database has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_oid: Option<u32>,
+}
+
+/// Typed value of a result cell or query parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum SqlValue {
+    /// NULL value
+    Null,
+    /// Boolean value
+    Bool(bool),
+    /// Integer value (up to `int8`; runtimes may map it to `BigInt`)
+    Int(i64),
+    /// Floating point value
+    Float(f64),
+    /// Arbitrary precision decimal (`numeric`), as its text representation
+    Numeric(String),
+    /// Text value
+    Text(String),
+    /// Binary value (`bytea`, `BLOB`)
+    Bytes(Vec<u8>),
+    /// JSON value (`json`, `jsonb`)
+    Json(serde_json::Value),
+    /// Timestamp in microseconds since the Unix epoch, UTC
+    Timestamp(i64),
+    /// UUID in its hyphenated lowercase form
+    Uuid(String),
+    /// Array value (elements may be arrays for multi-dimensional arrays)
+    Array(Vec<SqlValue>),
+}
+
+impl SqlValue {
+    /// JSON representation, matching the legacy `DatabaseResult::Rows` format
+    ///
+    /// Integers outside the safe JS range and numerics become strings,
+    /// bytes become arrays of numbers, timestamps become RFC 3339 strings.
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+        match self {
+            Self::Null => Value::Null,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Int(i) if i.unsigned_abs() <= MAX_SAFE_INTEGER as u64 => Value::from(*i),
+            Self::Int(i) => Value::String(i.to_string()),
+            Self::Float(f) => Value::from(*f),
+            Self::Numeric(s) | Self::Text(s) | Self::Uuid(s) => Value::String(s.clone()),
+            Self::Bytes(bytes) => Value::from(bytes.clone()),
+            Self::Json(value) => value.clone(),
+            Self::Timestamp(micros) => Value::String(rfc3339(*micros)),
+            Self::Array(values) => Value::Array(values.iter().map(Self::to_json).collect()),
+        }
+    }
+
+    /// Approximate in-memory size of the value in bytes, for result limits
+    pub fn byte_size(&self) -> usize {
+        match self {
+            Self::Null | Self::Bool(_) => 1,
+            Self::Int(_) | Self::Float(_) | Self::Timestamp(_) => 8,
+            Self::Numeric(s) | Self::Text(s) | Self::Uuid(s) => s.len(),
+            Self::Bytes(bytes) => bytes.len(),
+            Self::Json(value) => value.to_string().len(),
+            Self::Array(values) => values.iter().map(Self::byte_size).sum(),
+        }
+    }
+}
+
+/// Structured result of a query
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    /// Result columns (empty for statements that return no rows)
+    pub columns: Vec<SqlColumn>,
+    /// Rows, each with one value per column
+    pub rows: Vec<Vec<SqlValue>>,
+    /// Rows inserted, updated, deleted or returned
+    pub rows_affected: u64,
+    /// Command tag (`SELECT`, `INSERT`, `UPDATE`, ...)
+    pub command: String,
+}
+
+impl QueryResult {
+    /// Rows as a JSON array of objects keyed by column name
+    pub fn to_json(&self) -> serde_json::Value {
+        let rows = self.rows.iter().map(|row| {
+            let object = self
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.name.clone(), value.to_json()))
+                .collect();
+
+            serde_json::Value::Object(object)
+        });
+
+        serde_json::Value::Array(rows.collect())
+    }
+}
+
+/// Default number of rows per batch of a streamed query
+pub const QUERY_STREAM_BATCH_SIZE: u32 = 100;
+
+/// Batch of rows of a streamed query
+pub type RowBatch = Vec<Vec<SqlValue>>;
+
+/// Streamed query result: columns up front, rows in batches
+///
+/// Same channel shape as `StorageStream`: a bounded receiver, so the
+/// database is only read as fast as the worker consumes rows. Dropping the
+/// stream cancels the query.
+///
+/// Producers enforce `QueryLimits` as they read rows; `with_limits` checks
+/// them again on the receiving side, for producers that cannot.
+pub struct QueryStream {
+    /// Result columns
+    pub columns: Vec<SqlColumn>,
+    rows: mpsc::Receiver<Result<RowBatch, DatabaseError>>,
+    limits: Option<QueryLimits>,
+    received_rows: u64,
+    received_bytes: u64,
+}
+
+impl std::fmt::Debug for QueryStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryStream({} columns, ...)", self.columns.len())
+    }
+}
+
+impl QueryStream {
+    /// Create a stream and the sender used to feed it batches
+    pub fn channel(
+        columns: Vec<SqlColumn>,
+        buffer_size: usize,
+    ) -> (mpsc::Sender<Result<RowBatch, DatabaseError>>, Self) {
+        let (tx, rx) = mpsc::channel(buffer_size);
+
+        (
+            tx,
+            Self {
+                columns,
+                rows: rx,
+                limits: None,
+                received_rows: 0,
+                received_bytes: 0,
+            },
+        )
+    }
+
+    /// Fail the stream once it yields more rows or bytes than `limits`
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Next batch of rows, `None` once all rows were received
+    ///
+    /// After an error (from the database or a limit), the query is cancelled
+    /// and the stream ends.
+    pub async fn next_batch(&mut self) -> Option<Result<RowBatch, DatabaseError>> {
+        let batch = match self.rows.recv().await? {
+            Ok(batch) => batch,
+            Err(err) => {
+                self.rows.close();
+                return Some(Err(err));
+            }
+        };
+
+        self.received_rows += batch.len() as u64;
+        self.received_bytes += batch
+            .iter()
+            .flatten()
+            .map(|value| value.byte_size() as u64)
+            .sum::<u64>();
+
+        if let Some(limits) = &self.limits
+            && let Err(message) = limits.check(self.received_rows, self.received_bytes)
+        {
+            self.rows.close();
+            return Some(Err(DatabaseError::new(message)));
+        }
+
+        Some(Ok(batch))
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.ffffffZ` for a timestamp in microseconds
+pub(crate) fn rfc3339(micros: i64) -> String {
+    let seconds = micros.div_euclid(1_000_000);
+    let fraction = micros.rem_euclid(1_000_000);
+    let days = seconds.div_euclid(86_400);
+    let rem = seconds.rem_euclid(86_400);
+
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60,
+        fraction
+    )
+}
+
+/// Stable category of a database error, for retry logic in workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DatabaseErrorKind {
+    /// Unique, foreign key, not-null or check constraint (SQLSTATE class 23)
+    ConstraintViolation,
+    /// Serialization failure or deadlock: the transaction can be retried
+    SerializationFailure,
+    /// Statement timeout, cancellation or lock timeout
+    Timeout,
+    /// Insufficient privilege or failed authentication
+    Permission,
+    /// Syntax error or reference to an undefined object (SQLSTATE class 42)
+    Syntax,
+    /// Invalid data: bad input syntax, out of range value, division by zero
+    Data,
+    /// Connection lost or database unavailable
+    Connection,
+    /// Anything else
+    Other,
+}
+
+impl DatabaseErrorKind {
+    /// Category of a PostgreSQL SQLSTATE code
+    pub fn from_sqlstate(code: &str) -> Self {
+        match code {
+            "40001" | "40P01" => Self::SerializationFailure,
+            "57014" | "55P03" => Self::Timeout,
+            "42501" => Self::Permission,
+            "57P01" | "57P02" | "57P03" => Self::Connection,
+            _ => match code.get(..2) {
+                Some("23") => Self::ConstraintViolation,
+                Some("28") => Self::Permission,
+                Some("42") => Self::Syntax,
+                Some("22") => Self::Data,
+                Some("08") => Self::Connection,
+                _ => Self::Other,
+            },
+        }
+    }
+
+    /// Whether running the same statement again may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::SerializationFailure | Self::Timeout | Self::Connection
+        )
+    }
+}
+
+/// Database error with the fields PostgreSQL reports
+///
+/// Only `message` and `kind` are always set; errors raised by the runner
+/// itself (unknown binding, limits) have no SQLSTATE.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseError {
+    /// Primary error message
+    pub message: String,
+    /// Error category
+    pub kind: DatabaseErrorKind,
+    /// SQLSTATE code (`23505`, `40001`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Violated constraint name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<String>,
+    /// Secondary message with more detail
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Suggestion on how to fix the problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// 1-based character position of the error in the statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+}
+
+impl DatabaseError {
+    /// Error without SQLSTATE (category `Other`)
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            kind: DatabaseErrorKind::Other,
+            code: None,
+            constraint: None,
+            detail: None,
+            hint: None,
+            position: None,
+        }
+    }
+
+    /// Set the SQLSTATE code and the matching category
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        let code = code.into();
+        self.kind = DatabaseErrorKind::from_sqlstate(&code);
+        self.code = Some(code);
+        self
+    }
+
+    /// Override the category
+    pub fn with_kind(mut self, kind: DatabaseErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Whether running the same statement again may succeed
+    pub fn is_retryable(&self) -> bool {
+        self.kind.is_retryable()
+    }
+}
+
+impl std::fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "{} (SQLSTATE {})", self.message, code),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<String> for DatabaseError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for DatabaseError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
@@ -0,0 +1,122 @@
+//! Durable Object types shared by durable object operations and alarm events
+//!
+//! A durable object is a single-threaded actor identified by a
+//! `DurableObjectId` within a namespace (the binding). Workers get a stub for
+//! an object through `DurableObjectOp` and send it requests; the runner hosts
+//! each object as a `Worker` instance that receives those requests as fetch
+//! events, one at a time, and reaches its private storage through
+//! `DurableStorageOp`. Field names serialize in camelCase to match the
+//! Cloudflare Durable Objects API.
+
+use serde::{Deserialize, Serialize};
+
+/// Identity of a durable object within a namespace
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurableObjectId {
+    /// Opaque id (lowercase hex), unique within the namespace
+    pub id: String,
+    /// Name the id was derived from (`idFromName`), if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl DurableObjectId {
+    /// Id for an object without a name (`newUniqueId`, `idFromString`)
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: None,
+        }
+    }
+
+    /// Deterministic id for a name within a namespace (`idFromName`)
+    ///
+    /// The same namespace and name always give the same 32-digit hex id.
+    pub fn from_name(namespace: &str, name: impl Into<String>) -> Self {
+        let name = name.into();
+
+        // Two FNV-1a 64 lanes with different offset bases
+        let id = [0xcbf2_9ce4_8422_2325u64, 0x6c62_272e_07bb_0142]
+            .iter()
+            .map(|basis| {
+                let hash = namespace
+                    .bytes()
+                    .chain([0])
+                    .chain(name.bytes())
+                    .fold(*basis, |hash, byte| {
+                        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+                    });
+                format!("{:016x}", hash)
+            })
+            .collect();
+
+        Self {
+            id,
+            name: Some(name),
+        }
+    }
+}
+
+impl std::fmt::Display for DurableObjectId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+/// How a worker designates the object it wants a stub for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum DurableObjectTarget {
+    /// Object named `name` (`idFromName`)
+    Name(String),
+    /// Object with an existing id (`idFromString`)
+    Id(String),
+    /// New object with a fresh id (`newUniqueId`)
+    Unique,
+}
+
+/// Options for listing the keys of a durable object's storage
+///
+/// Keys are returned in lexicographic (byte) order, in the `[start, end)`
+/// range, reversed if `reverse` is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurableListOptions {
+    /// First key to return (inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// Return keys after this one (exclusive, takes precedence over `start`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<String>,
+    /// Last key (exclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// Only keys starting with this prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Return keys in descending order
+    #[serde(default)]
+    pub reverse: bool,
+    /// Maximum number of keys to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+impl DurableListOptions {
+    /// Whether `key` is selected by the range and prefix (ignores the limit)
+    pub fn matches(&self, key: &str) -> bool {
+        let after_start = match (&self.start_after, &self.start) {
+            (Some(after), _) => key > after.as_str(),
+            (None, Some(start)) => key >= start.as_str(),
+            (None, None) => true,
+        };
+
+        after_start
+            && self.end.as_ref().is_none_or(|end| key < end.as_str())
+            && self
+                .prefix
+                .as_ref()
+                .is_none_or(|prefix| key.starts_with(prefix.as_str()))
+    }
+}
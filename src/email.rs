@@ -0,0 +1,322 @@
+//! Email types shared by email operations and email events
+//!
+//! Outbound mail goes through `Operation::BindingEmail` as an
+//! `EmailMessage` (envelope addresses and raw MIME bytes); the runner checks
+//! it against the binding's `EmailPolicy` before handing it to its mail
+//! transport. Inbound mail is delivered in an `Event::Email`, and the worker
+//! answers with an `EmailOutcome` (forward, reject). Build and read the raw
+//! bytes with `MimeBuilder` and `MimeMessage`.
+
+use crate::MimeMessage;
+use crate::mime::{mailbox_address, mailbox_list};
+use serde::{Deserialize, Serialize};
+
+/// Email with its envelope
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailMessage {
+    /// Envelope sender (`MAIL FROM`)
+    pub from: String,
+    /// Envelope recipient (`RCPT TO`)
+    pub to: String,
+    /// Raw MIME message (headers and body)
+    pub raw: Vec<u8>,
+}
+
+impl EmailMessage {
+    pub fn new(from: impl Into<String>, to: impl Into<String>, raw: Vec<u8>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            raw,
+        }
+    }
+}
+
+/// Forward requested by an email handler
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailForward {
+    /// Recipient to forward to
+    pub to: String,
+    /// Extra headers to add to the forwarded message
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// Result of an email handler invocation
+///
+/// A message neither rejected nor forwarded is dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailOutcome {
+    /// Rejection reason (`setReject()`), returned to the sending server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reject: Option<String>,
+    /// Forwards (`forward()`), in order
+    #[serde(default)]
+    pub forwards: Vec<EmailForward>,
+    /// Error thrown by the handler, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl EmailOutcome {
+    /// Failed invocation (runners typically reject with a temporary error)
+    pub fn err(msg: impl Into<String>) -> Self {
+        Self {
+            error: Some(msg.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Sending policy of an email binding
+///
+/// Allowlist entries are addresses (`alice@example.com`) or whole domains
+/// (`@example.com`), compared case-insensitively. `None` allows any address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailPolicy {
+    /// Allowed envelope senders
+    #[serde(default)]
+    pub allowed_senders: Option<Vec<String>>,
+    /// Allowed envelope recipients
+    #[serde(default)]
+    pub allowed_recipients: Option<Vec<String>>,
+    /// Maximum raw message size in bytes (default: 25 MiB)
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
+}
+
+fn default_max_message_size() -> usize {
+    25 * 1024 * 1024
+}
+
+impl Default for EmailPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_senders: None,
+            allowed_recipients: None,
+            max_message_size: default_max_message_size(),
+        }
+    }
+}
+
+impl EmailPolicy {
+    /// Only send from these senders
+    pub fn with_senders(mut self, senders: Vec<String>) -> Self {
+        self.allowed_senders = Some(senders);
+        self
+    }
+
+    /// Only send to these recipients
+    pub fn with_recipients(mut self, recipients: Vec<String>) -> Self {
+        self.allowed_recipients = Some(recipients);
+        self
+    }
+
+    /// Validate a message to send (addresses, allowlists and size)
+    ///
+    /// The headers must agree with the envelope: the `From` header is the
+    /// envelope sender, and the `To` header lists the envelope recipient
+    /// with only allowed recipients.
+    pub fn check(&self, message: &EmailMessage) -> Result<(), String> {
+        check_address("sender", &message.from, &self.allowed_senders)?;
+        check_address("recipient", &message.to, &self.allowed_recipients)?;
+
+        if message.raw.len() > self.max_message_size {
+            return Err(format!(
+                "Message too large: {} bytes (max {})",
+                message.raw.len(),
+                self.max_message_size
+            ));
+        }
+
+        let parsed =
+            MimeMessage::parse(&message.raw).map_err(|e| format!("Invalid message: {}", e))?;
+
+        let from = single_header(&parsed, "From")?;
+        let from = mailbox_address(from).ok_or_else(|| format!("Invalid From header: {}", from))?;
+
+        // The envelope sender was checked against the allowlist above
+        if !from.eq_ignore_ascii_case(&message.from) {
+            return Err(format!(
+                "From header {} does not match sender {}",
+                from, message.from
+            ));
+        }
+
+        let mut to_envelope = false;
+
+        for mailbox in mailbox_list(single_header(&parsed, "To")?) {
+            let address = mailbox_address(mailbox)
+                .ok_or_else(|| format!("Invalid To header mailbox: {}", mailbox))?;
+
+            check_address("recipient", address, &self.allowed_recipients)?;
+            to_envelope |= address.eq_ignore_ascii_case(&message.to);
+        }
+
+        if !to_envelope {
+            return Err(format!("To header does not list recipient {}", message.to));
+        }
+
+        Ok(())
+    }
+}
+
+/// Value of a header that must appear exactly once
+fn single_header<'a>(message: &'a MimeMessage, name: &str) -> Result<&'a str, String> {
+    let mut values = message
+        .headers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str());
+
+    match (values.next(), values.next()) {
+        (Some(value), None) => Ok(value),
+        (None, _) => Err(format!("Missing {} header", name)),
+        (Some(_), Some(_)) => Err(format!("Duplicate {} header", name)),
+    }
+}
+
+fn check_address(role: &str, address: &str, allowed: &Option<Vec<String>>) -> Result<(), String> {
+    // Envelope addresses are bare: no display name
+    if mailbox_address(address) != Some(address) {
+        return Err(format!("Invalid {} address: {}", role, address));
+    }
+
+    let Some(allowed) = allowed else {
+        return Ok(());
+    };
+
+    let domain = &address[address.rfind('@').unwrap_or_default()..];
+
+    let is_allowed = allowed
+        .iter()
+        .any(|entry| entry.eq_ignore_ascii_case(address) || entry.eq_ignore_ascii_case(domain));
+
+    if !is_allowed {
+        return Err(format!("{} not allowed: {}", capitalize(role), address));
+    }
+
+    Ok(())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MimeBuilder;
+
+    fn email(from: &str, to: &str, builder: MimeBuilder) -> EmailMessage {
+        let raw = builder.subject("Hi").text("Hello").build().unwrap();
+        EmailMessage::new(from, to, raw)
+    }
+
+    fn policy() -> EmailPolicy {
+        EmailPolicy::default()
+            .with_senders(vec!["noreply@shop.example".into()])
+            .with_recipients(vec!["@example.com".into(), "bob@other.example".into()])
+    }
+
+    fn builder() -> MimeBuilder {
+        MimeBuilder::new()
+            .from("Shop <noreply@shop.example>")
+            .to("alice@example.com")
+    }
+
+    #[test]
+    fn allows_matching_messages() {
+        let message = email("noreply@shop.example", "alice@example.com", builder());
+        assert_eq!(policy().check(&message), Ok(()));
+        assert_eq!(EmailPolicy::default().check(&message), Ok(()));
+
+        // Several allowed recipients, display names with commas
+        let builder = builder()
+            .to("\"Doe, Bob\" <BOB@other.example>")
+            .cc("carol@elsewhere.example");
+        let message = email("noreply@shop.example", "bob@other.example", builder);
+        assert_eq!(policy().check(&message), Ok(()));
+    }
+
+    #[test]
+    fn rejects_envelope_outside_allowlists() {
+        let message = email("other@shop.example", "alice@example.com", builder());
+        let err = policy().check(&message).unwrap_err();
+        assert!(err.starts_with("Sender not allowed"), "{}", err);
+
+        let message = email(
+            "noreply@shop.example",
+            "mallory@evil.example",
+            builder().to("mallory@evil.example"),
+        );
+        let err = policy().check(&message).unwrap_err();
+        assert!(err.starts_with("Recipient not allowed"), "{}", err);
+
+        let message = email(
+            "Shop <noreply@shop.example>",
+            "alice@example.com",
+            builder(),
+        );
+        let err = EmailPolicy::default().check(&message).unwrap_err();
+        assert!(err.starts_with("Invalid sender address"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_headers_not_matching_envelope() {
+        let spoofed = builder().from("Bank <security@bank.example>");
+        let message = email("noreply@shop.example", "alice@example.com", spoofed);
+        let err = policy().check(&message).unwrap_err();
+        assert!(err.starts_with("From header"), "{}", err);
+
+        // An allowed envelope recipient cannot carry other To addresses
+        let message = email(
+            "noreply@shop.example",
+            "alice@example.com",
+            builder().to("mallory@evil.example"),
+        );
+        let err = policy().check(&message).unwrap_err();
+        assert!(err.starts_with("Recipient not allowed"), "{}", err);
+
+        let message = email("noreply@shop.example", "bob@other.example", builder());
+        let err = policy().check(&message).unwrap_err();
+        assert!(err.starts_with("To header does not list"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_missing_or_duplicate_headers() {
+        let raw = b"From: noreply@shop.example\r\nSubject: Hi\r\n\r\nHello".to_vec();
+        let message = EmailMessage::new("noreply@shop.example", "alice@example.com", raw);
+        assert_eq!(policy().check(&message), Err("Missing To header".into()));
+
+        let raw = b"From: noreply@shop.example\r\nFrom: security@bank.example\r\n\
+                    To: alice@example.com\r\n\r\nHello"
+            .to_vec();
+        let message = EmailMessage::new("noreply@shop.example", "alice@example.com", raw);
+        assert_eq!(
+            policy().check(&message),
+            Err("Duplicate From header".into())
+        );
+    }
+
+    #[test]
+    fn rejects_large_messages() {
+        let policy = EmailPolicy {
+            max_message_size: 64,
+            ..EmailPolicy::default()
+        };
+        let message = email("noreply@shop.example", "alice@example.com", builder());
+
+        let err = policy.check(&message).unwrap_err();
+        assert!(err.starts_with("Message too large"), "{}", err);
+    }
+}
@@ -3,16 +3,41 @@
 //! This crate provides shared types used across all JS runtime implementations
 //! (Deno, V8, QuickJS, JSC, Boa).
 
+mod analytics;
+#[cfg(any(feature = "memory", feature = "sqlite", feature = "fs"))]
+mod backends;
+mod cache;
+mod database;
+mod date;
+mod durable;
+mod email;
 mod http;
 mod limits;
 mod log;
+mod mime;
 mod ops;
+mod queue;
+mod rate_limit;
+mod rpc;
 mod script;
+mod secret;
+#[cfg(feature = "sigv4")]
+mod sigv4;
+mod socket;
+mod storage;
 mod task;
 mod termination;
 mod websocket;
 mod worker;
 
+pub use analytics::{AnalyticsBuffer, AnalyticsDataPoint};
+pub use cache::{CacheControl, CachePolicy, CacheQueryOptions};
+pub use database::{
+    DatabaseError, DatabaseErrorKind, QUERY_STREAM_BATCH_SIZE, QueryResult, QueryStream, RowBatch,
+    SqlColumn, SqlValue,
+};
+pub use durable::{DurableListOptions, DurableObjectId, DurableObjectTarget};
+pub use email::{EmailForward, EmailMessage, EmailOutcome, EmailPolicy};
 pub use http::{
     HttpMethod, HttpRequest, HttpResponse, HttpResponseMeta, RequestBody, ResponseBody,
     ResponseSender,
@@ -21,15 +46,44 @@ pub use http::{
 #[cfg(feature = "hyper")]
 pub use http::{HyperBody, StreamBody};
 
-pub use limits::{BindingLimit, RuntimeLimits};
+#[cfg(feature = "fs")]
+pub use backends::FsBackend;
+#[cfg(feature = "sqlite")]
+pub use backends::SqliteBackend;
+#[cfg(feature = "memory")]
+pub use backends::{MemoryCache, MemoryDurableStorage, MemoryKv, MemoryQueue, MemoryRateLimiter};
+
+pub use limits::{
+    AnalyticsLimits, BindingLimit, DurableStorageLimits, KvLimits, QueryLimits, QueueLimits,
+    RuntimeLimits,
+};
 pub use log::{LogEvent, LogLevel};
+pub use mime::{MimeBuilder, MimeMessage};
 pub use ops::{
-    DatabaseOp, DatabaseResult, DefaultOps, DirectOperations, KvOp, KvResult, OpFuture, Operation,
-    OperationResult, OperationsHandle, OperationsHandler, SqlParam, SqlPrimitive, StorageOp,
-    StorageResult,
+    CacheOp, CacheResult, DatabaseOp, DatabaseResult, DefaultOps, DirectOperations,
+    DurableObjectOp, DurableObjectResult, DurableStorageOp, DurableStorageResult, KvOp, KvResult,
+    OpFuture, Operation, OperationResult, OperationsHandle, OperationsHandler, QueueOp,
+    QueueResult, SqlParam, SqlPrimitive, SqlStatement, StorageOp, StorageResult, TypedParam,
+};
+pub use queue::{QueueBatchResult, QueueBody, QueueMessage, QueueOutcome, QueueSend};
+pub use rate_limit::{RateLimitAlgorithm, RateLimitConfig, RateLimitOutcome};
+pub use rpc::{RpcCall, RpcError, RpcResult};
+pub use script::{BindingConfig, BindingInfo, BindingType, NotFoundHandling, Script, WorkerCode};
+pub use secret::Secret;
+#[cfg(feature = "sigv4")]
+pub use sigv4::{PRESIGN_MAX_EXPIRES_IN, SigV4Signer};
+pub use socket::{
+    EgressPolicy, SocketConnection, SocketEndpoint, SocketIncoming, SocketOutgoing, SocketTls,
+};
+pub use storage::{
+    Checksums, Conditional, ContentRange, DELETE_MAX_KEYS, GetOptions, HttpMetadata, ListInclude,
+    ListOptions, MULTIPART_MAX_PARTS, MULTIPART_MIN_PART_SIZE, ObjectMetadata, PutOptions,
+    ReadRejection, StorageObject, StorageRange, StorageStream, UploadedPart,
+};
+pub use task::{
+    AlarmInit, EmailInit, Event, EventType, FetchInit, QueueInit, RpcInit, TaskInit, TaskResult,
+    TaskSource,
 };
-pub use script::{BindingInfo, BindingType, Script, WorkerCode};
-pub use task::{Event, EventType, FetchInit, TaskInit, TaskResult, TaskSource};
 pub use termination::TerminationReason;
 pub use websocket::{WebSocketConnection, WebSocketId, WebSocketIncoming, WebSocketOutgoing};
 pub use worker::Worker;
@@ -1,3 +1,6 @@
+use crate::{AnalyticsDataPoint, QueueSend};
+use std::time::Duration;
+
 /// Limit configuration for a specific binding (fetch, KV, database, etc.)
 #[derive(Debug, Clone)]
 pub struct BindingLimit {
@@ -58,6 +61,23 @@ pub struct RuntimeLimits {
     pub database_limit: BindingLimit,
     /// Storage (R2/S3) limit (default: 100 total, 3 concurrent)
     pub storage_limit: BindingLimit,
+    /// Outgoing TCP socket limit (default: 20 total, 6 open at once)
+    pub socket_limit: BindingLimit,
+
+    /// Maximum rows read from a streamed database query (default: 1M, 0 = unlimited)
+    pub max_query_rows: u64,
+    /// Maximum bytes read from a streamed database query (default: 256MB, 0 = unlimited)
+    pub max_query_bytes: u64,
+}
+
+impl RuntimeLimits {
+    /// Limits of streamed database query results
+    pub fn query_limits(&self) -> QueryLimits {
+        QueryLimits {
+            max_rows: self.max_query_rows,
+            max_bytes: self.max_query_bytes,
+        }
+    }
 }
 
 impl Default for RuntimeLimits {
@@ -74,6 +94,322 @@ impl Default for RuntimeLimits {
             kv_limit: BindingLimit::new(1000, 10), // 1000 total, 10 concurrent
             database_limit: BindingLimit::new(100, 5), // 100 total, 5 concurrent
             storage_limit: BindingLimit::new(100, 3), // 100 total, 3 concurrent
+            socket_limit: BindingLimit::new(20, 6), // 20 total, 6 open at once
+
+            // Streamed query results (exports, reports)
+            max_query_rows: 1_000_000,
+            max_query_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Size limits of a streamed database query result
+///
+/// Checked by the producer as it reads rows, so a query over the limit stops
+/// at the first row that exceeds it.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryLimits {
+    /// Maximum rows (default: 1M, 0 = unlimited)
+    pub max_rows: u64,
+    /// Maximum bytes of values, see `SqlValue::byte_size` (default: 256MB, 0 = unlimited)
+    pub max_bytes: u64,
+}
+
+impl QueryLimits {
+    /// Validate the size of a result read so far
+    pub fn check(&self, rows: u64, bytes: u64) -> Result<(), String> {
+        if self.max_rows > 0 && rows > self.max_rows {
+            return Err(format!("Query result exceeds {} rows", self.max_rows));
+        }
+
+        if self.max_bytes > 0 && bytes > self.max_bytes {
+            return Err(format!("Query result exceeds {} bytes", self.max_bytes));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        RuntimeLimits::default().query_limits()
+    }
+}
+
+/// Data limits for KV bindings (key/value sizes, TTL, list page size)
+///
+/// Used by the reference backends to validate `KvOp`s the same way
+/// production stores do.
+#[derive(Debug, Clone)]
+pub struct KvLimits {
+    /// Maximum key size in bytes (default: 512)
+    pub max_key_size: usize,
+    /// Maximum serialized value size in bytes (default: 25 MiB)
+    pub max_value_size: usize,
+    /// Minimum TTL in seconds for `expires_in` (default: 60)
+    pub min_expiration_ttl: u64,
+    /// Maximum (and default) number of keys returned by `List` (default: 1000)
+    pub max_list_limit: u32,
+}
+
+impl KvLimits {
+    /// Validate a key (non-empty, at most `max_key_size` bytes)
+    pub fn check_key(&self, key: &str) -> Result<(), String> {
+        check_key(key, self.max_key_size)
+    }
+
+    /// Validate a value (at most `max_value_size` bytes once serialized)
+    pub fn check_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        check_value(value, self.max_value_size)
+    }
+
+    /// Validate a TTL in seconds, returning it as a `Duration`
+    pub fn check_expires_in(&self, expires_in: Option<u64>) -> Result<Option<Duration>, String> {
+        match expires_in {
+            Some(ttl) if ttl < self.min_expiration_ttl => Err(format!(
+                "Invalid expires_in: {} (min {} seconds)",
+                ttl, self.min_expiration_ttl
+            )),
+            Some(ttl) => Ok(Some(Duration::from_secs(ttl))),
+            None => Ok(None),
+        }
+    }
+
+    /// Effective page size for a `List` operation
+    pub fn list_limit(&self, limit: Option<u32>) -> u32 {
+        limit
+            .unwrap_or(self.max_list_limit)
+            .min(self.max_list_limit)
+    }
+}
+
+impl Default for KvLimits {
+    fn default() -> Self {
+        Self {
+            max_key_size: 512,
+            max_value_size: 25 * 1024 * 1024,
+            min_expiration_ttl: 60,
+            max_list_limit: 1000,
+        }
+    }
+}
+
+/// Data limits for queue bindings (message size, batch size, delays, retries)
+///
+/// Applies to sends (`QueueOp`) and to deliveries.
+#[derive(Debug, Clone)]
+pub struct QueueLimits {
+    /// Maximum encoded message body size in bytes (default: 128 KiB)
+    pub max_message_size: usize,
+    /// Maximum messages per `SendBatch` and per delivered batch (default: 100)
+    pub max_batch_size: usize,
+    /// Maximum delay in seconds for sends and retries (default: 12 hours)
+    pub max_delay_seconds: u32,
+    /// Retries of a message after its first delivery before it moves to the
+    /// dead letters (default: 3, so up to 4 deliveries)
+    pub max_retries: u32,
+}
+
+impl QueueLimits {
+    /// Validate a message (body size and delay)
+    pub fn check_message(&self, message: &QueueSend) -> Result<(), String> {
+        let size = message.body.byte_size();
+
+        if size > self.max_message_size {
+            return Err(format!(
+                "Message too large: {} bytes (max {})",
+                size, self.max_message_size
+            ));
+        }
+
+        self.check_delay(message.delay_seconds)
+    }
+
+    /// Validate a delay in seconds
+    pub fn check_delay(&self, delay_seconds: Option<u32>) -> Result<(), String> {
+        match delay_seconds {
+            Some(delay) if delay > self.max_delay_seconds => Err(format!(
+                "Invalid delay: {} (max {} seconds)",
+                delay, self.max_delay_seconds
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate the number of messages of a batch
+    pub fn check_batch(&self, len: usize) -> Result<(), String> {
+        if len == 0 || len > self.max_batch_size {
+            return Err(format!(
+                "Invalid batch size: {} (must be 1 to {})",
+                len, self.max_batch_size
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_message_size: 128 * 1024,
+            max_batch_size: 100,
+            max_delay_seconds: 12 * 60 * 60,
+            max_retries: 3,
+        }
+    }
+}
+
+/// Data limits for durable object storage (key/value sizes, keys per call)
+#[derive(Debug, Clone)]
+pub struct DurableStorageLimits {
+    /// Maximum key size in bytes (default: 2 KiB)
+    pub max_key_size: usize,
+    /// Maximum serialized value size in bytes (default: 128 KiB)
+    pub max_value_size: usize,
+    /// Maximum keys per get, put or delete (default: 128)
+    pub max_keys: usize,
+}
+
+impl DurableStorageLimits {
+    /// Validate a key (non-empty, at most `max_key_size` bytes)
+    pub fn check_key(&self, key: &str) -> Result<(), String> {
+        check_key(key, self.max_key_size)
+    }
+
+    /// Validate a value (at most `max_value_size` bytes once serialized)
+    pub fn check_value(&self, value: &serde_json::Value) -> Result<(), String> {
+        check_value(value, self.max_value_size)
+    }
+
+    /// Validate the number of keys of a single call
+    pub fn check_keys(&self, len: usize) -> Result<(), String> {
+        if len > self.max_keys {
+            return Err(format!("Too many keys: {} (max {})", len, self.max_keys));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for DurableStorageLimits {
+    fn default() -> Self {
+        Self {
+            max_key_size: 2048,
+            max_value_size: 128 * 1024,
+            max_keys: 128,
+        }
+    }
+}
+
+/// Data limits for analytics bindings (data point shape, points per execution)
+///
+/// Data points over these limits are dropped by `AnalyticsBuffer`.
+#[derive(Debug, Clone)]
+pub struct AnalyticsLimits {
+    /// Maximum blobs per data point (default: 20)
+    pub max_blobs: usize,
+    /// Maximum total blob size per data point in bytes (default: 16 KiB)
+    pub max_blob_bytes: usize,
+    /// Maximum doubles per data point (default: 20)
+    pub max_doubles: usize,
+    /// Maximum indexes per data point (default: 1)
+    pub max_indexes: usize,
+    /// Maximum index size in bytes (default: 96)
+    pub max_index_size: usize,
+    /// Maximum data points per execution (default: 250)
+    pub max_points_per_execution: usize,
+}
+
+impl AnalyticsLimits {
+    /// Validate the shape of a data point
+    pub fn check_point(&self, point: &AnalyticsDataPoint) -> Result<(), String> {
+        if point.blobs.len() > self.max_blobs {
+            return Err(format!(
+                "Too many blobs: {} (max {})",
+                point.blobs.len(),
+                self.max_blobs
+            ));
+        }
+
+        if point.blob_bytes() > self.max_blob_bytes {
+            return Err(format!(
+                "Blobs too large: {} bytes (max {})",
+                point.blob_bytes(),
+                self.max_blob_bytes
+            ));
+        }
+
+        if point.doubles.len() > self.max_doubles {
+            return Err(format!(
+                "Too many doubles: {} (max {})",
+                point.doubles.len(),
+                self.max_doubles
+            ));
+        }
+
+        if point.indexes.len() > self.max_indexes {
+            return Err(format!(
+                "Too many indexes: {} (max {})",
+                point.indexes.len(),
+                self.max_indexes
+            ));
+        }
+
+        if let Some(index) = point.indexes.iter().find(|i| i.len() > self.max_index_size) {
+            return Err(format!(
+                "Index too large: {} bytes (max {})",
+                index.len(),
+                self.max_index_size
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AnalyticsLimits {
+    fn default() -> Self {
+        Self {
+            max_blobs: 20,
+            max_blob_bytes: 16 * 1024,
+            max_doubles: 20,
+            max_indexes: 1,
+            max_index_size: 96,
+            max_points_per_execution: 250,
         }
     }
 }
+
+/// Validate a key (non-empty, at most `max_size` bytes)
+fn check_key(key: &str, max_size: usize) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Key must not be empty".into());
+    }
+
+    if key.len() > max_size {
+        return Err(format!(
+            "Key too large: {} bytes (max {})",
+            key.len(),
+            max_size
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate a value (at most `max_size` bytes once serialized)
+fn check_value(value: &serde_json::Value, max_size: usize) -> Result<(), String> {
+    let size = serde_json::to_vec(value)
+        .map_err(|e| format!("Invalid value: {}", e))?
+        .len();
+
+    if size > max_size {
+        return Err(format!(
+            "Value too large: {} bytes (max {})",
+            size, max_size
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1016 @@
+//! MIME message builder and parser (RFC 5322, RFC 2045-2047, RFC 2231)
+//!
+//! Covers what workers need to send and read mail: headers (with encoded
+//! words), text and HTML bodies, attachments, multipart nesting and the
+//! base64 and quoted-printable transfer encodings. Messages are bytes in
+//! and bytes out, so they can be passed as `EmailMessage::raw` unchanged.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum line length of a 7bit body (RFC 5322 limit, without CRLF)
+const MAX_LINE_LENGTH: usize = 998;
+
+/// Line length of base64 bodies
+const BASE64_LINE_LENGTH: usize = 76;
+
+/// Line length header fields are folded at, when they have whitespace
+const HEADER_LINE_LENGTH: usize = 78;
+
+/// Maximum length of an encoded word (RFC 2047, section 2)
+const ENCODED_WORD_LENGTH: usize = 75;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+static BOUNDARIES: AtomicU64 = AtomicU64::new(0);
+
+/// MIME entity: a whole message or one part of a multipart body
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MimeMessage {
+    /// Header fields in order, unfolded (values may contain encoded words)
+    pub headers: Vec<(String, String)>,
+    /// Body as transmitted (still transfer-encoded)
+    pub body: Vec<u8>,
+}
+
+impl MimeMessage {
+    /// Parse a message from its raw bytes (CRLF or LF line endings)
+    pub fn parse(raw: &[u8]) -> Result<Self, String> {
+        let (head, body) = match find(raw, b"\r\n\r\n") {
+            Some(i) => (&raw[..i], &raw[i + 4..]),
+            None => match find(raw, b"\n\n") {
+                Some(i) => (&raw[..i], &raw[i + 2..]),
+                // Headers only
+                None => (raw, &raw[raw.len()..]),
+            },
+        };
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+
+        for line in String::from_utf8_lossy(head).lines() {
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with([' ', '\t']) {
+                let (_, value) = headers
+                    .last_mut()
+                    .ok_or_else(|| "Invalid header: continuation line first".to_string())?;
+                if !value.is_empty() {
+                    value.push(' ');
+                }
+                value.push_str(line.trim());
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid header line: {}", line))?;
+
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+
+        Ok(Self {
+            headers,
+            body: body.to_vec(),
+        })
+    }
+
+    /// First value of a header, by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Header value with encoded words (`=?utf-8?B?...?=`) decoded
+    pub fn decoded_header(&self, name: &str) -> Option<String> {
+        self.header(name).map(decode_words)
+    }
+
+    /// Decoded `Subject` header
+    pub fn subject(&self) -> Option<String> {
+        self.decoded_header("subject")
+    }
+
+    /// Media type in lowercase, without parameters (default: `text/plain`)
+    pub fn content_type(&self) -> String {
+        self.header("content-type")
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "text/plain".to_string())
+    }
+
+    /// Parameter of a structured header (`boundary`, `charset`, `filename`...)
+    pub fn header_param(&self, name: &str, param: &str) -> Option<String> {
+        self.header(name)?.split(';').skip(1).find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case(param)
+                .then(|| value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    /// Whether the part is an attachment (`Content-Disposition: attachment`)
+    pub fn is_attachment(&self) -> bool {
+        self.header("content-disposition").is_some_and(|value| {
+            value
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("attachment")
+        })
+    }
+
+    /// File name of an attachment, if any
+    ///
+    /// `filename*` (RFC 2231) takes precedence over `filename` and `name`.
+    pub fn filename(&self) -> Option<String> {
+        if let Some(name) = self
+            .header_param("content-disposition", "filename*")
+            .and_then(|value| decode_extended_value(&value))
+        {
+            return Some(name);
+        }
+
+        self.header_param("content-disposition", "filename")
+            .or_else(|| self.header_param("content-type", "name"))
+            .map(|name| decode_words(&name))
+    }
+
+    /// Body with the transfer encoding (base64, quoted-printable) removed
+    pub fn decoded_body(&self) -> Result<Vec<u8>, String> {
+        let encoding = self
+            .header("content-transfer-encoding")
+            .map(|value| value.trim().to_ascii_lowercase());
+
+        match encoding.as_deref() {
+            Some("base64") => base64_decode(&self.body),
+            Some("quoted-printable") => Ok(quoted_printable_decode(&self.body)),
+            _ => Ok(self.body.clone()),
+        }
+    }
+
+    /// Parts of a multipart body (empty if the body is not multipart)
+    pub fn parts(&self) -> Result<Vec<MimeMessage>, String> {
+        if !self.content_type().starts_with("multipart/") {
+            return Ok(Vec::new());
+        }
+
+        let boundary = self
+            .header_param("content-type", "boundary")
+            .ok_or_else(|| "Multipart body without boundary".to_string())?;
+
+        let delimiter = format!("--{}", boundary);
+        let closing = format!("--{}--", boundary);
+
+        let mut parts = Vec::new();
+        let mut start: Option<usize> = None;
+        let mut offset = 0;
+
+        while offset < self.body.len() {
+            let end = find(&self.body[offset..], b"\n").map_or(self.body.len(), |i| offset + i + 1);
+            let line = String::from_utf8_lossy(&self.body[offset..end]);
+            let line = line.trim_end();
+
+            if line == delimiter || line == closing {
+                if let Some(start) = start {
+                    // The line break before a delimiter belongs to it
+                    let mut part_end = offset.max(start);
+
+                    for suffix in [b'\n', b'\r'] {
+                        if part_end > start && self.body[part_end - 1] == suffix {
+                            part_end -= 1;
+                        }
+                    }
+
+                    parts.push(Self::parse(&self.body[start..part_end])?);
+                }
+
+                if line == closing {
+                    return Ok(parts);
+                }
+
+                start = Some(end);
+            }
+
+            offset = end;
+        }
+
+        Err("Multipart body without closing delimiter".into())
+    }
+
+    /// Decoded plain text body (first `text/plain` part that is not an attachment)
+    pub fn text(&self) -> Option<String> {
+        self.find_body("text/plain")
+    }
+
+    /// Decoded HTML body (first `text/html` part that is not an attachment)
+    pub fn html(&self) -> Option<String> {
+        self.find_body("text/html")
+    }
+
+    /// Attachments, in order, from any depth
+    pub fn attachments(&self) -> Vec<MimeMessage> {
+        let mut attachments = Vec::new();
+
+        for part in self.parts().unwrap_or_default() {
+            if part.is_attachment() {
+                attachments.push(part);
+            } else {
+                attachments.extend(part.attachments());
+            }
+        }
+
+        attachments
+    }
+
+    fn find_body(&self, content_type: &str) -> Option<String> {
+        if self.content_type() == content_type && !self.is_attachment() {
+            let body = self.decoded_body().ok()?;
+            return Some(String::from_utf8_lossy(&body).into_owned());
+        }
+
+        self.parts()
+            .ok()?
+            .iter()
+            .find_map(|part| part.find_body(content_type))
+    }
+}
+
+/// Attachment added by `MimeBuilder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Attachment {
+    filename: String,
+    content_type: String,
+    content: Vec<u8>,
+}
+
+/// Builder for a MIME message
+///
+/// # Example
+///
+/// ```ignore
+/// let raw = MimeBuilder::new()
+///     .from("Shop <noreply@shop.example>")
+///     .to("alice@example.com")
+///     .subject("Your order")
+///     .text("Thanks for your order!")
+///     .html("<p>Thanks for your order!</p>")
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MimeBuilder {
+    headers: Vec<(String, String)>,
+    text: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+}
+
+impl MimeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `From` mailbox (`addr@domain` or `Name <addr@domain>`)
+    ///
+    /// Display names of mailboxes are encoded if not ASCII.
+    pub fn from(self, mailbox: impl Into<String>) -> Self {
+        self.header("From", encode_mailbox(&mailbox.into()))
+    }
+
+    /// Add a `To` mailbox
+    pub fn to(self, mailbox: impl Into<String>) -> Self {
+        self.append("To", encode_mailbox(&mailbox.into()))
+    }
+
+    /// Add a `Cc` mailbox
+    pub fn cc(self, mailbox: impl Into<String>) -> Self {
+        self.append("Cc", encode_mailbox(&mailbox.into()))
+    }
+
+    /// Set the `Reply-To` mailbox
+    pub fn reply_to(self, mailbox: impl Into<String>) -> Self {
+        self.header("Reply-To", encode_mailbox(&mailbox.into()))
+    }
+
+    /// Set the subject (encoded if not ASCII)
+    pub fn subject(self, subject: impl Into<String>) -> Self {
+        self.header("Subject", encode_word(&subject.into()))
+    }
+
+    /// Set a header, replacing any previous value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Append a mailbox to an address list header
+    fn append(mut self, name: &str, mailbox: String) -> Self {
+        match self
+            .headers
+            .iter_mut()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        {
+            Some((_, value)) => {
+                value.push_str(", ");
+                value.push_str(&mailbox);
+            }
+            None => self.headers.push((name.to_string(), mailbox)),
+        }
+
+        self
+    }
+
+    /// Set the plain text body
+    pub fn text(mut self, body: impl Into<String>) -> Self {
+        self.text = Some(body.into());
+        self
+    }
+
+    /// Set the HTML body
+    pub fn html(mut self, body: impl Into<String>) -> Self {
+        self.html = Some(body.into());
+        self
+    }
+
+    /// Add an attachment
+    pub fn attachment(
+        mut self,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        content: Vec<u8>,
+    ) -> Self {
+        self.attachments.push(Attachment {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            content,
+        });
+        self
+    }
+
+    /// Build the raw message (CRLF line endings)
+    ///
+    /// Adds `Date`, `Message-ID` and `MIME-Version` unless set, and folds
+    /// long header lines. Fails without a valid `From` mailbox, or if a
+    /// header or the content type of an attachment contains a line break.
+    pub fn build(self) -> Result<Vec<u8>, String> {
+        let from = self
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("from"))
+            .and_then(|(_, value)| mailbox_address(value))
+            .ok_or_else(|| "Missing or invalid From mailbox".to_string())?;
+
+        let domain = from.rsplit('@').next().unwrap_or_default().to_string();
+
+        if let Some((name, _)) = self
+            .headers
+            .iter()
+            .find(|(name, value)| has_line_break(name) || has_line_break(value))
+        {
+            return Err(format!("Invalid header {}: contains a line break", name));
+        }
+
+        if let Some(attachment) = self
+            .attachments
+            .iter()
+            .find(|attachment| has_line_break(&attachment.content_type))
+        {
+            return Err(format!(
+                "Invalid content type of attachment {}: contains a line break",
+                attachment.filename
+            ));
+        }
+
+        let body = self.body_entity();
+        let mut headers = self.headers;
+        let mut defaults = vec![
+            (
+                "Date".to_string(),
+                httpdate::fmt_http_date(SystemTime::now()),
+            ),
+            (
+                "Message-ID".to_string(),
+                format!("<{}@{}>", unique(), domain),
+            ),
+            ("MIME-Version".to_string(), "1.0".to_string()),
+        ];
+        defaults.retain(|(name, _)| {
+            !headers
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case(name))
+        });
+        headers.extend(defaults);
+
+        let mut out = Vec::new();
+
+        for (name, value) in &headers {
+            out.extend_from_slice(fold(name, value).as_bytes());
+        }
+
+        out.extend(body);
+
+        Ok(out)
+    }
+
+    /// Content headers and body of the message
+    fn body_entity(&self) -> Vec<u8> {
+        let body = match (&self.text, &self.html) {
+            (Some(text), Some(html)) => multipart(
+                "alternative",
+                vec![text_part("text/plain", text), text_part("text/html", html)],
+            ),
+            (None, Some(html)) => text_part("text/html", html),
+            (Some(text), None) => text_part("text/plain", text),
+            (None, None) => text_part("text/plain", ""),
+        };
+
+        if self.attachments.is_empty() {
+            return body;
+        }
+
+        let mut parts = vec![body];
+
+        for attachment in &self.attachments {
+            let filename = attachment.filename.replace(['"', '\r', '\n'], "");
+
+            // Non-ASCII names: RFC 2231 for the disposition, an encoded
+            // word for readers that only know the `name` parameter
+            let disposition = if filename.is_ascii() {
+                format!("attachment; filename=\"{}\"", filename)
+            } else {
+                format!("attachment; filename*={}", encode_extended_value(&filename))
+            };
+
+            let mut part = format!(
+                "Content-Type: {}; name=\"{}\"\r\nContent-Disposition: {}\r\nContent-Transfer-Encoding: base64\r\n\r\n",
+                attachment.content_type,
+                encode_word(&filename),
+                disposition
+            )
+            .into_bytes();
+            part.extend(base64_lines(&attachment.content));
+            parts.push(part);
+        }
+
+        multipart("mixed", parts)
+    }
+}
+
+/// Address of a mailbox (`addr@domain` or `Name <addr@domain>`), if valid
+pub(crate) fn mailbox_address(mailbox: &str) -> Option<&str> {
+    let mailbox = mailbox.trim();
+
+    let address = match (mailbox.rfind('<'), mailbox.strip_suffix('>')) {
+        (Some(start), Some(rest)) => &rest[start + 1..],
+        _ => mailbox,
+    };
+
+    let (local, domain) = address.rsplit_once('@')?;
+
+    let valid = !local.is_empty()
+        && !domain.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "<>()[],;:\"\\".contains(c));
+
+    valid.then_some(address)
+}
+
+/// Mailboxes of an address list header, split on commas outside quotes
+/// and angle brackets (`a@example.com, "Doe, Jane" <jane@example.com>`)
+pub(crate) fn mailbox_list(value: &str) -> Vec<&str> {
+    let mut mailboxes = Vec::new();
+    let (mut quoted, mut bracketed, mut start) = (false, false, 0);
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '<' if !quoted => bracketed = true,
+            '>' if !quoted => bracketed = false,
+            ',' if !quoted && !bracketed => {
+                mailboxes.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    mailboxes.push(value[start..].trim());
+    mailboxes.retain(|mailbox| !mailbox.is_empty());
+    mailboxes
+}
+
+/// Single-part text entity (7bit if possible, base64 otherwise)
+fn text_part(content_type: &str, body: &str) -> Vec<u8> {
+    let seven_bit = body.is_ascii()
+        && !body.contains('\0')
+        && body.lines().all(|line| line.len() <= MAX_LINE_LENGTH);
+
+    let mut part = format!("Content-Type: {}; charset=utf-8\r\n", content_type).into_bytes();
+
+    if seven_bit {
+        part.extend_from_slice(b"Content-Transfer-Encoding: 7bit\r\n\r\n");
+        part.extend_from_slice(body.lines().collect::<Vec<_>>().join("\r\n").as_bytes());
+    } else {
+        part.extend_from_slice(b"Content-Transfer-Encoding: base64\r\n\r\n");
+        part.extend(base64_lines(body.as_bytes()));
+    }
+
+    part
+}
+
+/// Multipart entity from complete parts (headers and body)
+fn multipart(subtype: &str, parts: Vec<Vec<u8>>) -> Vec<u8> {
+    let boundary = format!("=_{}", unique());
+
+    let mut out = format!(
+        "Content-Type: multipart/{}; boundary=\"{}\"\r\n\r\n",
+        subtype, boundary
+    )
+    .into_bytes();
+
+    for part in parts {
+        out.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        out.extend(part);
+        out.extend_from_slice(b"\r\n");
+    }
+
+    out.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    out
+}
+
+/// Unique token for boundaries and message ids
+fn unique() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+
+    format!(
+        "{:016x}.{:x}",
+        nanos,
+        BOUNDARIES.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn has_line_break(value: &str) -> bool {
+    value.contains(['\r', '\n'])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Encoded words for a header value, if it is not printable ASCII
+///
+/// Long values are split into several words of at most
+/// `ENCODED_WORD_LENGTH` characters, separated by spaces (where `build`
+/// folds the line). Characters are never split across words.
+fn encode_word(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        return value.to_string();
+    }
+
+    // `=?utf-8?B?` and `?=` around the base64 text, 3 bytes per 4 characters
+    let max_bytes = (ENCODED_WORD_LENGTH - 12) / 4 * 3;
+
+    let mut words = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        if i + c.len_utf8() - start > max_bytes {
+            words.push(&value[start..i]);
+            start = i;
+        }
+    }
+
+    words.push(&value[start..]);
+
+    words
+        .iter()
+        .map(|word| {
+            format!(
+                "=?utf-8?B?{}?=",
+                String::from_utf8(base64_encode(word.as_bytes())).unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Mailbox with its display name encoded if not ASCII
+fn encode_mailbox(mailbox: &str) -> String {
+    match mailbox.rfind('<') {
+        Some(start) if !mailbox[..start].is_ascii() => {
+            let name = mailbox[..start].trim().trim_matches('"');
+            format!("{} {}", encode_word(name), &mailbox[start..])
+        }
+        _ => mailbox.to_string(),
+    }
+}
+
+/// Header line(s) `Name: value`, folded at spaces to `HEADER_LINE_LENGTH`
+fn fold(name: &str, value: &str) -> String {
+    let mut out = format!("{}:", name);
+    let mut line_length = out.len();
+
+    for word in value.split(' ') {
+        // Only fold before a word (possibly right after the name)
+        if !word.is_empty() && line_length + 1 + word.len() > HEADER_LINE_LENGTH {
+            out.push_str("\r\n");
+            line_length = 0;
+        }
+
+        out.push(' ');
+        out.push_str(word);
+        line_length += 1 + word.len();
+    }
+
+    out.push_str("\r\n");
+    out
+}
+
+/// RFC 2231 parameter value (`utf-8''` and percent-encoded UTF-8)
+fn encode_extended_value(value: &str) -> String {
+    let mut out = String::from("utf-8''");
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    out
+}
+
+/// Decode an RFC 2231 parameter value (`charset'language'percent-encoded`)
+fn decode_extended_value(value: &str) -> Option<String> {
+    let mut fields = value.splitn(3, '\'');
+    let (charset, _language, text) = (fields.next()?, fields.next()?, fields.next()?);
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+
+    let decoded = if charset.eq_ignore_ascii_case("iso-8859-1") {
+        bytes.iter().map(|byte| *byte as char).collect()
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+
+    Some(decoded)
+}
+
+/// Decode the encoded words of a header value (B and Q encodings)
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+
+        let Some((word, tail)) = decode_word(candidate) else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+            continue;
+        };
+
+        // Whitespace between adjacent encoded words is dropped
+        if !(after_word && before.trim().is_empty()) {
+            out.push_str(before);
+        }
+
+        out.push_str(&word);
+        rest = tail;
+        after_word = true;
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Decode one encoded word at the start of `input`, returning the remainder
+fn decode_word(input: &str) -> Option<(String, &str)> {
+    let inner = input.strip_prefix("=?")?;
+    let (charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let (text, tail) = inner.split_once("?=")?;
+
+    let bytes = match encoding {
+        "B" | "b" => base64_decode(text.as_bytes()).ok()?,
+        "Q" | "q" => quoted_printable_decode(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+
+    let decoded = if charset.eq_ignore_ascii_case("iso-8859-1") {
+        bytes.iter().map(|byte| *byte as char).collect()
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+
+    Some((decoded, tail))
+}
+
+fn base64_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i)) as usize & 63]);
+            } else {
+                out.push(b'=');
+            }
+        }
+    }
+
+    out
+}
+
+/// Base64 body split in lines of `BASE64_LINE_LENGTH`
+fn base64_lines(data: &[u8]) -> Vec<u8> {
+    base64_encode(data)
+        .chunks(BASE64_LINE_LENGTH)
+        .collect::<Vec<_>>()
+        .join(&b"\r\n"[..])
+}
+
+/// Decode base64, ignoring whitespace
+fn base64_decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in data {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            byte if byte.is_ascii_whitespace() => continue,
+            _ => return Err(format!("Invalid base64 byte: 0x{:02x}", byte)),
+        };
+
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode quoted-printable (soft line breaks and `=XX` escapes)
+fn quoted_printable_decode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+
+    while i < data.len() {
+        if data[i] != b'=' {
+            out.push(data[i]);
+            i += 1;
+            continue;
+        }
+
+        match &data[i + 1..] {
+            [b'\r', b'\n', ..] => i += 3,
+            [b'\n', ..] => i += 2,
+            [high, low, ..] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                let hex = [*high, *low];
+                let hex = std::str::from_utf8(&hex).unwrap_or_default();
+                out.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+                i += 3;
+            }
+            // Malformed escape: keep it as is
+            _ => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> MimeBuilder {
+        MimeBuilder::new()
+            .from("Shop <noreply@shop.example>")
+            .to("alice@example.com")
+    }
+
+    #[test]
+    fn base64_rfc_vectors() {
+        // RFC 4648, section 10
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (decoded, encoded) in vectors {
+            assert_eq!(base64_encode(decoded.as_bytes()), encoded.as_bytes());
+            assert_eq!(
+                base64_decode(encoded.as_bytes()).unwrap(),
+                decoded.as_bytes()
+            );
+        }
+
+        // Line breaks of encoded bodies are ignored (RFC 2045, section 6.8)
+        assert_eq!(base64_decode(b"Zm9v\r\nYmFy").unwrap(), b"foobar");
+        assert!(base64_decode(b"Zm9v!").is_err());
+    }
+
+    #[test]
+    fn round_trips_text_html_and_attachments() {
+        let content: Vec<u8> = (0..=255).cycle().take(1000).collect();
+
+        let raw = builder()
+            .subject("Your order é")
+            .text("Thanks!\nSee you.")
+            .html("<p>Merci à vous</p>")
+            .attachment("invoice.pdf", "application/pdf", content.clone())
+            .attachment("notes.txt", "text/plain", b"notes".to_vec())
+            .build()
+            .unwrap();
+
+        let message = MimeMessage::parse(&raw).unwrap();
+
+        assert_eq!(message.subject().as_deref(), Some("Your order é"));
+        assert_eq!(message.text().as_deref(), Some("Thanks!\r\nSee you."));
+        assert_eq!(message.html().as_deref(), Some("<p>Merci à vous</p>"));
+
+        let attachments = message.attachments();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].filename().as_deref(), Some("invoice.pdf"));
+        assert_eq!(attachments[0].content_type(), "application/pdf");
+        assert_eq!(attachments[0].decoded_body().unwrap(), content);
+        assert_eq!(attachments[1].filename().as_deref(), Some("notes.txt"));
+        assert_eq!(attachments[1].decoded_body().unwrap(), b"notes");
+    }
+
+    #[test]
+    fn round_trips_single_part() {
+        let raw = builder().html("<p>Hi</p>").build().unwrap();
+        let message = MimeMessage::parse(&raw).unwrap();
+
+        assert_eq!(message.content_type(), "text/html");
+        assert_eq!(message.html().as_deref(), Some("<p>Hi</p>"));
+        assert_eq!(message.text(), None);
+        assert!(message.attachments().is_empty());
+    }
+
+    /// Header section of a raw message, one physical line per entry
+    fn header_lines(raw: &[u8]) -> Vec<String> {
+        let end = find(raw, b"\r\n\r\n").unwrap();
+        String::from_utf8(raw[..end].to_vec())
+            .unwrap()
+            .split("\r\n")
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn splits_long_encoded_words_and_folds_headers() {
+        let subject = format!("Commande confirmée : {}", "ééé ".repeat(30));
+        let raw = builder().subject(subject.as_str()).build().unwrap();
+
+        let lines = header_lines(&raw);
+        assert!(lines.iter().all(|line| line.len() <= 78 && line.is_ascii()));
+        assert!(lines.iter().any(|line| line.starts_with(" =?utf-8?B?")));
+
+        for line in &lines {
+            for word in line.split(' ').filter(|word| word.starts_with("=?")) {
+                assert!(word.len() <= 75, "{}", word);
+            }
+        }
+
+        let message = MimeMessage::parse(&raw).unwrap();
+        assert_eq!(message.subject(), Some(subject));
+
+        // Plain ASCII values are folded at spaces, without encoding
+        let subject = "word ".repeat(40).trim_end().to_string();
+        let raw = builder().subject(subject.as_str()).build().unwrap();
+        assert!(header_lines(&raw).iter().all(|line| line.len() <= 78));
+        assert!(!String::from_utf8_lossy(&raw).contains("=?"));
+        assert_eq!(MimeMessage::parse(&raw).unwrap().subject(), Some(subject));
+    }
+
+    #[test]
+    fn encodes_display_names() {
+        let raw = MimeBuilder::new()
+            .from("\"Zoë Müller\" <zoe@shop.example>")
+            .to("Ana Lúcia <ana@example.com>")
+            .to("bob@example.com")
+            .build()
+            .unwrap();
+
+        assert!(header_lines(&raw).iter().all(|line| line.is_ascii()));
+
+        let message = MimeMessage::parse(&raw).unwrap();
+        assert_eq!(
+            message.decoded_header("from").as_deref(),
+            Some("Zoë Müller <zoe@shop.example>")
+        );
+        assert_eq!(
+            message.decoded_header("to").as_deref(),
+            Some("Ana Lúcia <ana@example.com>, bob@example.com")
+        );
+        assert_eq!(
+            message.header("from").and_then(mailbox_address),
+            Some("zoe@shop.example")
+        );
+    }
+
+    #[test]
+    fn encodes_attachment_filenames() {
+        let raw = builder()
+            .text("See attached")
+            .attachment("résumé 2024.pdf", "application/pdf", b"%PDF".to_vec())
+            .build()
+            .unwrap();
+
+        let text = String::from_utf8(raw.clone()).unwrap();
+        assert!(text.contains("filename*=utf-8''r%C3%A9sum%C3%A9%202024.pdf"));
+
+        let attachments = MimeMessage::parse(&raw).unwrap().attachments();
+        assert_eq!(
+            attachments[0].filename().as_deref(),
+            Some("résumé 2024.pdf")
+        );
+        assert_eq!(
+            attachments[0].decoded_header("content-type").as_deref(),
+            Some("application/pdf; name=\"résumé 2024.pdf\"")
+        );
+
+        // RFC 2231, section 4
+        assert_eq!(
+            decode_extended_value("iso-8859-1'en'%A3%20rates").as_deref(),
+            Some("£ rates")
+        );
+        assert_eq!(decode_extended_value("utf-8''%zz"), None);
+    }
+
+    #[test]
+    fn rejects_line_breaks() {
+        let injected = builder()
+            .text("Hi")
+            .attachment(
+                "a.txt",
+                "text/plain\r\nBcc: mallory@example.com",
+                b"a".to_vec(),
+            )
+            .build();
+        assert!(injected.is_err());
+
+        let injected = builder()
+            .header("X-Tag", "a\r\nBcc: mallory@example.com")
+            .build();
+        assert!(injected.is_err());
+    }
+}
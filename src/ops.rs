@@ -19,47 +19,144 @@
 //!
 //! Runners only need to override the methods they want to implement.
 
-use crate::{HttpRequest, HttpResponse, LogLevel, WebSocketConnection};
+use crate::{
+    AnalyticsDataPoint, CacheQueryOptions, DatabaseError, DurableListOptions, DurableObjectId,
+    DurableObjectTarget, EgressPolicy, EmailMessage, GetOptions, HttpMethod, HttpRequest,
+    HttpResponse, ListOptions, LogLevel, ObjectMetadata, PutOptions, QueryResult, QueryStream,
+    QueueSend, RateLimitOutcome, RpcCall, RpcResult, Secret, SocketConnection, SocketTls, SqlValue,
+    StorageObject, StorageStream, UploadedPart, WebSocketConnection,
+};
 use std::collections::HashMap;
 use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
 use std::pin::Pin;
 use std::sync::Arc;
 
-/// Storage operation types for get/put/head/list/delete/fetch
-#[derive(Debug, Clone)]
+/// Storage operation types for get/put/head/list/copy/delete/fetch
+///
+/// Not `Clone`: `PutStream` carries a [`StorageStream`] body, which can
+/// only be consumed once. Runners retrying an operation must keep what they
+/// need to rebuild it.
+#[derive(Debug)]
 pub enum StorageOp {
-    /// Get an object by key (returns body as bytes)
-    Get { key: String },
+    /// Get an object by key (returns body and metadata)
+    Get { key: String, options: GetOptions },
+    /// Get an object by key as a stream (returns `StorageResult::Stream`)
+    GetStream { key: String, options: GetOptions },
     /// Fetch an object by key (returns full HTTP response with headers)
-    Fetch { key: String },
-    /// Put an object (key + body)
-    Put { key: String, body: Vec<u8> },
+    ///
+    /// Conditions and ranges map to 304, 412, 206 and 416 responses.
+    Fetch { key: String, options: GetOptions },
+    /// Put an object (key + body + metadata)
+    Put {
+        key: String,
+        body: Vec<u8>,
+        options: PutOptions,
+    },
+    /// Put an object from a stream (key + streaming body + metadata)
+    PutStream {
+        key: String,
+        body: StorageStream,
+        options: PutOptions,
+    },
     /// Head (metadata only) for an object
     Head { key: String },
-    /// List objects with optional prefix
-    List {
-        prefix: Option<String>,
-        limit: Option<u32>,
+    /// List objects with optional prefix, delimiter and metadata
+    List { options: ListOptions },
+    /// Copy an object with its metadata (returns `StorageResult::Metadata`)
+    ///
+    /// Returns `NotFound` if `from` doesn't exist; `to` is replaced if it exists.
+    Copy { from: String, to: String },
+    /// Delete objects (at most `DELETE_MAX_KEYS`, missing keys are ignored)
+    Delete { keys: Vec<String> },
+    /// Create a time-limited URL for direct access to an object (returns
+    /// `StorageResult::Presigned`)
+    ///
+    /// `method` is the HTTP method the URL is valid for (usually GET or PUT),
+    /// `expires_in` its validity in seconds.
+    Presign {
+        key: String,
+        method: HttpMethod,
+        expires_in: u64,
     },
-    /// Delete an object
-    Delete { key: String },
+    /// Start a multipart upload (returns `StorageResult::MultipartUpload`)
+    CreateMultipartUpload { key: String, options: PutOptions },
+    /// Upload one part of a multipart upload (returns `StorageResult::UploadedPart`)
+    ///
+    /// Uploading the same part number again replaces the previous part.
+    UploadPart {
+        key: String,
+        upload_id: String,
+        part_number: u16,
+        body: Vec<u8>,
+    },
+    /// Assemble uploaded parts into the final object (returns `StorageResult::Metadata`)
+    ///
+    /// Parts must be listed in ascending part number order, with the etags
+    /// returned by `UploadPart`. Every part but the last must be at least
+    /// `MULTIPART_MIN_PART_SIZE` bytes. Parts not listed are discarded.
+    CompleteMultipartUpload {
+        key: String,
+        upload_id: String,
+        parts: Vec<UploadedPart>,
+    },
+    /// Abort a multipart upload and discard its parts
+    AbortMultipartUpload { key: String, upload_id: String },
 }
 
 /// Result from a storage operation
 #[derive(Debug)]
 pub enum StorageResult {
-    /// Object body (for get) or empty (for put/delete success)
+    /// Empty success (for delete and abort_multipart_upload)
     Body(Option<Vec<u8>>),
+    /// Object body and metadata (for get)
+    Object(StorageObject),
+    /// Streaming object body and metadata (for get_stream)
+    Stream(StorageObject<StorageStream>),
     /// Full HTTP response (for fetch)
     Response(HttpResponse),
-    /// Metadata from head operation
-    Head { size: u64, etag: Option<String> },
-    /// List of keys
-    List { keys: Vec<String>, truncated: bool },
+    /// Object metadata (for head, put, copy and complete_multipart_upload)
+    Metadata(ObjectMetadata),
+    /// Key doesn't exist (for get, get_stream, head and copy)
+    NotFound,
+    /// Conditions of a get did not hold: no body, only metadata
+    ///
+    /// `not_modified` distinguishes "not modified" (`if-none-match`,
+    /// `if-modified-since`) from "precondition failed" (`if-match`,
+    /// `if-unmodified-since`).
+    PreconditionFailed {
+        metadata: ObjectMetadata,
+        not_modified: bool,
+    },
+    /// Range of a get outside of the object: no body, only metadata
+    RangeNotSatisfiable { metadata: ObjectMetadata },
+    /// Presigned URL (for presign)
+    Presigned { url: String },
+    /// Multipart upload handle (for create_multipart_upload)
+    MultipartUpload { key: String, upload_id: String },
+    /// Uploaded part (for upload_part)
+    UploadedPart(UploadedPart),
+    /// Objects and delimited prefixes matching a list operation, in key order
+    ///
+    /// `cursor` is set when `truncated`; pass it in `ListOptions::cursor` to
+    /// get the next page.
+    List {
+        objects: Vec<ObjectMetadata>,
+        delimited_prefixes: Vec<String>,
+        truncated: bool,
+        cursor: Option<String>,
+    },
     /// Error message
     Error(String),
 }
 
+impl StorageResult {
+    /// `Metadata`, or `NotFound` if the object doesn't exist
+    pub fn metadata(metadata: Option<ObjectMetadata>) -> Self {
+        metadata.map_or(Self::NotFound, Self::Metadata)
+    }
+}
+
 /// SQL primitive value - used directly or within arrays
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
@@ -76,10 +173,40 @@ pub enum SqlPrimitive {
     String(String),
 }
 
-/// SQL parameter value - primitives or arrays of primitives
+impl From<&SqlPrimitive> for SqlValue {
+    fn from(primitive: &SqlPrimitive) -> Self {
+        match primitive {
+            SqlPrimitive::Null => Self::Null,
+            SqlPrimitive::Bool(b) => Self::Bool(*b),
+            SqlPrimitive::Int(i) => Self::Int(*i),
+            SqlPrimitive::Float(f) => Self::Float(*f),
+            SqlPrimitive::String(s) => Self::Text(s.clone()),
+        }
+    }
+}
+
+/// SQL parameter with an explicit type
+///
+/// Serialized as `{ "type": "bytes", "value": [1, 2] }`, optionally with a
+/// database type name (`{ "type": "text", "value": "{}", "typeName": "jsonb" }`)
+/// for runners that bind parameters by type (PostgreSQL).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedParam {
+    /// Parameter value, tagged with its type
+    #[serde(flatten)]
+    pub value: SqlValue,
+    /// Database type to bind as (`int4`, `timestamptz`, `jsonb`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+}
+
+/// SQL parameter value - primitives, arrays of primitives, or typed values
 ///
 /// Uses `#[serde(untagged)]` for transparent deserialization from JS values.
 /// PostgreSQL supports arrays of primitives (e.g., `WHERE id = ANY($1::int[])`).
+/// Values that plain JSON cannot express unambiguously (bytes, JSON objects,
+/// timestamps, UUIDs, decimals, nested arrays) use `Typed`.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum SqlParam {
@@ -87,6 +214,37 @@ pub enum SqlParam {
     Array(Vec<SqlPrimitive>),
     /// Single primitive value
     Primitive(SqlPrimitive),
+    /// Value with an explicit type
+    Typed(TypedParam),
+}
+
+impl SqlParam {
+    /// Parameter as a typed value (strings are `Text`, arrays are `Array`)
+    pub fn to_value(&self) -> SqlValue {
+        match self {
+            Self::Array(items) => SqlValue::Array(items.iter().map(SqlValue::from).collect()),
+            Self::Primitive(primitive) => primitive.into(),
+            Self::Typed(typed) => typed.value.clone(),
+        }
+    }
+
+    /// Explicit database type name, if any
+    pub fn type_name(&self) -> Option<&str> {
+        match self {
+            Self::Typed(typed) => typed.type_name.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// SQL statement with its parameters (for batches)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SqlStatement {
+    /// SQL statement
+    pub sql: String,
+    /// Query parameters
+    #[serde(default)]
+    pub params: Vec<SqlParam>,
 }
 
 /// Database operation types for SQL queries
@@ -99,15 +257,49 @@ pub enum DatabaseOp {
         /// Query parameters
         params: Vec<SqlParam>,
     },
+    /// Execute a SQL query, streaming rows (returns `DatabaseResult::Stream`)
+    ///
+    /// For results too large to buffer (exports, reports). Rows arrive in
+    /// batches of `batch_size` (default: `QUERY_STREAM_BATCH_SIZE`).
+    QueryStream {
+        /// SQL statement
+        sql: String,
+        /// Query parameters
+        params: Vec<SqlParam>,
+        /// Rows per batch
+        batch_size: Option<u32>,
+    },
+    /// Execute statements in order (returns `DatabaseResult::Batch`)
+    ///
+    /// When `transactional`, statements run in a single transaction: if one
+    /// fails, or the worker terminates before the batch completes, none of
+    /// them take effect. Otherwise each statement commits on its own and
+    /// execution stops at the first failure.
+    ///
+    /// Errors name the index of the failed statement.
+    Batch {
+        /// Statements to execute
+        statements: Vec<SqlStatement>,
+        /// Run all statements atomically (D1 `batch()` semantics)
+        transactional: bool,
+    },
 }
 
 /// Result from a database operation
 #[derive(Debug)]
 pub enum DatabaseResult {
     /// Rows as JSON array
+    ///
+    /// Legacy format: prefer `Query`, which keeps column types.
     Rows(String),
-    /// Error message
-    Error(String),
+    /// Structured result: columns, typed rows and command tag
+    Query(QueryResult),
+    /// One structured result per statement of a batch, in order
+    Batch(Vec<QueryResult>),
+    /// Streamed result (for query_stream)
+    Stream(QueryStream),
+    /// Error with SQLSTATE and category (build from a message with `.into()`)
+    Error(DatabaseError),
 }
 
 /// KV operation types for get/put/delete/list
@@ -127,6 +319,8 @@ pub enum KvOp {
     List {
         prefix: Option<String>,
         limit: Option<u32>,
+        /// Resume after this key (the last key of the previous page)
+        cursor: Option<String>,
     },
 }
 
@@ -143,7 +337,134 @@ pub enum KvResult {
     Error(String),
 }
 
+/// Queue operation types for send/send_batch
+#[derive(Debug, Clone)]
+pub enum QueueOp {
+    /// Send a message
+    Send { message: QueueSend },
+    /// Send messages at once (all or none are accepted)
+    SendBatch { messages: Vec<QueueSend> },
+}
+
+/// Result from a queue operation
+#[derive(Debug)]
+pub enum QueueResult {
+    /// Messages accepted
+    Ok,
+    /// Error message
+    Error(String),
+}
+
+/// Cache operation types for match/put/delete (Cache API)
+///
+/// Entries are keyed by request URL; see the `cache` module for the rules.
+#[derive(Debug)]
+pub enum CacheOp {
+    /// Find a fresh response for a request
+    Match {
+        request: HttpRequest,
+        options: CacheQueryOptions,
+    },
+    /// Store a response for a request (not stored if not cacheable)
+    Put {
+        request: HttpRequest,
+        response: HttpResponse,
+    },
+    /// Remove the entries for a request (returns `CacheResult::Deleted`)
+    Delete {
+        request: HttpRequest,
+        options: CacheQueryOptions,
+    },
+}
+
+/// Result from a cache operation
+#[derive(Debug)]
+pub enum CacheResult {
+    /// Cached response (for match) - None on a miss
+    Response(Option<HttpResponse>),
+    /// Whether an entry was removed (for delete)
+    Deleted(bool),
+    /// Success (for put)
+    Ok,
+    /// Error message
+    Error(String),
+}
+
+/// Durable object namespace operation types for get/fetch
+#[derive(Debug)]
+pub enum DurableObjectOp {
+    /// Resolve a stub for an object (returns `DurableObjectResult::Id`)
+    ///
+    /// Does not start the object: it is created on its first request.
+    Get { target: DurableObjectTarget },
+    /// Send a request to an object (returns `DurableObjectResult::Response`)
+    ///
+    /// Requests to the same object are delivered one at a time, in order.
+    Fetch {
+        id: DurableObjectId,
+        request: HttpRequest,
+    },
+}
+
+/// Result from a durable object namespace operation
+#[derive(Debug)]
+pub enum DurableObjectResult {
+    /// Resolved object id (for get)
+    Id(DurableObjectId),
+    /// Response from the object (for fetch)
+    Response(HttpResponse),
+    /// Error message
+    Error(String),
+}
+
+/// Storage operation types for the durable object hosting the worker
+///
+/// Every operation is atomic. `Transaction` applies its operations in order
+/// and takes effect entirely or not at all.
+#[derive(Debug, Clone)]
+pub enum DurableStorageOp {
+    /// Get values by key (missing keys are omitted from the result)
+    Get { keys: Vec<String> },
+    /// Put values (key + JSON value)
+    Put {
+        entries: Vec<(String, serde_json::Value)>,
+    },
+    /// Delete keys (returns `DurableStorageResult::Deleted`)
+    Delete { keys: Vec<String> },
+    /// Delete all keys and the alarm
+    DeleteAll,
+    /// List entries in key order
+    List { options: DurableListOptions },
+    /// Run operations atomically (returns `DurableStorageResult::Transaction`)
+    Transaction { ops: Vec<DurableStorageOp> },
+    /// Get the scheduled alarm time (Unix timestamp in milliseconds)
+    GetAlarm,
+    /// Schedule the alarm, replacing any previous one (Unix timestamp in milliseconds)
+    SetAlarm { scheduled_time: u64 },
+    /// Cancel the alarm
+    DeleteAlarm,
+}
+
+/// Result from a durable object storage operation
+#[derive(Debug, Clone)]
+pub enum DurableStorageResult {
+    /// Entries in key order (for get/list)
+    Entries(Vec<(String, serde_json::Value)>),
+    /// Number of keys deleted (for delete)
+    Deleted(u64),
+    /// Scheduled alarm time, if any (for get_alarm)
+    Alarm(Option<u64>),
+    /// One result per operation, in order (for transaction)
+    Transaction(Vec<DurableStorageResult>),
+    /// Success (for put/delete_all/set_alarm/delete_alarm)
+    Ok,
+    /// Error message
+    Error(String),
+}
+
 /// Operations that runtimes delegate to the runner
+// Moved once per call: boxing large variants would only add allocations
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum Operation {
     /// Direct HTTP fetch (pass-through, no auth modification)
@@ -181,6 +502,57 @@ pub enum Operation {
         op: DatabaseOp,
     },
 
+    /// Queue operation (send/send_batch)
+    BindingQueue {
+        /// Binding name (e.g., "MY_QUEUE")
+        binding: String,
+        /// The operation to perform
+        op: QueueOp,
+    },
+
+    /// Cache API operation (match/put/delete)
+    Cache {
+        /// Cache name (`caches.open(name)`), None for `caches.default`
+        cache: Option<String>,
+        /// The operation to perform
+        op: CacheOp,
+    },
+
+    /// Durable object namespace operation (get stub/fetch)
+    BindingDurableObject {
+        /// Binding name (e.g., "ROOMS")
+        binding: String,
+        /// The operation to perform
+        op: DurableObjectOp,
+    },
+
+    /// Storage of the durable object hosting the worker (get/put/list/alarm)
+    DurableStorage(DurableStorageOp),
+
+    /// Email binding (send a message)
+    BindingEmail {
+        /// Binding name (e.g., "SEND_EMAIL")
+        binding: String,
+        /// The message to send
+        message: EmailMessage,
+    },
+
+    /// Rate limit binding (spend `cost` units for `key`)
+    BindingRateLimit {
+        /// Binding name (e.g., "API_LIMITER")
+        binding: String,
+        /// Key to count against (client IP, API token, ...)
+        key: String,
+        /// Units to spend (0 only reads the current state)
+        cost: u64,
+    },
+
+    /// Secret binding (fetch the value on first use)
+    BindingSecret {
+        /// Binding name (e.g., "API_TOKEN")
+        binding: String,
+    },
+
     /// Worker binding (worker-to-worker calls)
     BindingWorker {
         /// Binding name (e.g., "MY_WORKER")
@@ -189,6 +561,22 @@ pub enum Operation {
         request: HttpRequest,
     },
 
+    /// Worker binding RPC (call a method of the target worker)
+    BindingWorkerRpc {
+        /// Binding name (e.g., "MY_WORKER")
+        binding: String,
+        /// The method call
+        call: RpcCall,
+    },
+
+    /// Analytics data point (fire-and-forget)
+    BindingAnalytics {
+        /// Binding name (e.g., "MY_DATASET")
+        binding: String,
+        /// The data point to write
+        point: AnalyticsDataPoint,
+    },
+
     /// Log message (fire-and-forget)
     Log { level: LogLevel, message: String },
 
@@ -199,9 +587,21 @@ pub enum Operation {
         /// Additional headers for the handshake
         headers: HashMap<String, String>,
     },
+
+    /// Open an outgoing TCP socket (checked with `check_egress` and, once
+    /// resolved, `check_egress_ip` first)
+    SocketConnect {
+        /// Host name or IP address
+        host: String,
+        /// TCP port
+        port: u16,
+        /// Transport security
+        tls: SocketTls,
+    },
 }
 
 /// Results for operations
+#[allow(clippy::large_enum_variant)]
 pub enum OperationResult {
     /// HTTP response (used by Fetch and BindingFetch)
     Http(Result<HttpResponse, String>),
@@ -215,11 +615,38 @@ pub enum OperationResult {
     /// Database operation result
     Database(DatabaseResult),
 
-    /// Acknowledgement for fire-and-forget operations (Log, etc.)
+    /// Queue operation result
+    Queue(QueueResult),
+
+    /// Cache operation result
+    Cache(CacheResult),
+
+    /// Email sent (used by BindingEmail)
+    Email(Result<(), String>),
+
+    /// Rate limit outcome (used by BindingRateLimit)
+    RateLimit(Result<RateLimitOutcome, String>),
+
+    /// Secret value (used by BindingSecret)
+    Secret(Result<Secret, String>),
+
+    /// Durable object namespace operation result
+    DurableObject(DurableObjectResult),
+
+    /// Durable object storage operation result
+    DurableStorage(DurableStorageResult),
+
+    /// RPC result (used by BindingWorkerRpc)
+    Rpc(RpcResult),
+
+    /// Acknowledgement for fire-and-forget operations (Log, BindingAnalytics)
     Ack,
 
     /// WebSocket connection established (contains bidirectional channels)
     WebSocket(Result<WebSocketConnection, String>),
+
+    /// Socket connection established (contains bidirectional byte channels)
+    Socket(Result<SocketConnection, String>),
 }
 
 /// Future type alias for async operation results
@@ -298,7 +725,81 @@ pub trait OperationsHandler: Send + Sync {
         _op: DatabaseOp,
     ) -> OpFuture<'_, DatabaseResult> {
         let err = format!("Database binding '{}' not implemented", binding);
-        Box::pin(async move { DatabaseResult::Error(err) })
+        Box::pin(async move { DatabaseResult::Error(err.into()) })
+    }
+
+    /// Handle a queue operation (send/send_batch)
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_binding_queue(&self, binding: &str, _op: QueueOp) -> OpFuture<'_, QueueResult> {
+        let err = format!("Queue binding '{}' not implemented", binding);
+        Box::pin(async move { QueueResult::Error(err) })
+    }
+
+    /// Handle a Cache API operation (match/put/delete)
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_cache(&self, _cache: Option<&str>, _op: CacheOp) -> OpFuture<'_, CacheResult> {
+        Box::pin(async { CacheResult::Error("Cache not implemented".into()) })
+    }
+
+    /// Handle a durable object namespace operation (get stub/fetch)
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_binding_durable_object(
+        &self,
+        binding: &str,
+        _op: DurableObjectOp,
+    ) -> OpFuture<'_, DurableObjectResult> {
+        let err = format!("Durable Object binding '{}' not implemented", binding);
+        Box::pin(async move { DurableObjectResult::Error(err) })
+    }
+
+    /// Handle a storage operation of the durable object hosting the worker
+    ///
+    /// Default: returns error "not available" (the worker is not a durable object)
+    fn handle_durable_storage(&self, _op: DurableStorageOp) -> OpFuture<'_, DurableStorageResult> {
+        Box::pin(async {
+            DurableStorageResult::Error("Durable Object storage not available".into())
+        })
+    }
+
+    /// Handle an email binding (send a message)
+    ///
+    /// Runners check the message against the binding's `EmailPolicy`
+    /// before sending it.
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_binding_email(
+        &self,
+        binding: &str,
+        _message: EmailMessage,
+    ) -> OpFuture<'_, Result<(), String>> {
+        let err = format!("Email binding '{}' not implemented", binding);
+        Box::pin(async move { Err(err) })
+    }
+
+    /// Handle a rate limit binding (spend `cost` units for `key`)
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_binding_rate_limit(
+        &self,
+        binding: &str,
+        _key: &str,
+        _cost: u64,
+    ) -> OpFuture<'_, Result<RateLimitOutcome, String>> {
+        let err = format!("Rate limit binding '{}' not implemented", binding);
+        Box::pin(async move { Err(err) })
+    }
+
+    /// Handle a secret binding (fetch the value, e.g. from a vault)
+    ///
+    /// Called on each access, so runners can audit or cache as they see fit.
+    ///
+    /// Default: returns error "not configured"
+    fn handle_binding_secret(&self, binding: &str) -> OpFuture<'_, Result<Secret, String>> {
+        let err = format!("Secret binding '{}' not configured", binding);
+        Box::pin(async move { Err(err) })
     }
 
     /// Handle a worker binding (worker-to-worker call)
@@ -313,6 +814,14 @@ pub trait OperationsHandler: Send + Sync {
         Box::pin(async move { Err(err) })
     }
 
+    /// Handle a worker binding RPC (method call on the target worker)
+    ///
+    /// Default: returns error "not implemented"
+    fn handle_binding_worker_rpc(&self, binding: &str, _call: RpcCall) -> OpFuture<'_, RpcResult> {
+        let err = format!("Worker binding '{}' not implemented", binding);
+        Box::pin(async move { Err(err.into()) })
+    }
+
     /// Handle an outgoing WebSocket connection
     ///
     /// Default: returns error "WebSocket not available"
@@ -324,6 +833,63 @@ pub trait OperationsHandler: Send + Sync {
         Box::pin(async { Err("WebSocket not available".into()) })
     }
 
+    /// Handle an analytics data point (fire-and-forget)
+    ///
+    /// Runners typically push it to an `AnalyticsBuffer` and write the
+    /// buffered points after the execution.
+    ///
+    /// Default: discards the data point
+    fn handle_binding_analytics(&self, _binding: &str, _point: AnalyticsDataPoint) {}
+
+    /// Check that an outgoing connection is allowed (egress policy hook)
+    ///
+    /// Called by `handle` before resolving the host.
+    ///
+    /// Default: `EgressPolicy::default()` (public addresses, any port but 25)
+    fn check_egress(&self, host: &str, port: u16) -> Result<(), String> {
+        EgressPolicy::default().check(host, port)
+    }
+
+    /// Check a resolved address of an outgoing connection (egress policy hook)
+    ///
+    /// Called by `handle` for every address returned by `resolve_socket_host`,
+    /// so that names resolving to private networks are rejected too.
+    ///
+    /// Default: `EgressPolicy::default()` (public addresses)
+    fn check_egress_ip(&self, ip: IpAddr) -> Result<(), String> {
+        EgressPolicy::default().check_ip(ip)
+    }
+
+    /// Resolve the host of an outgoing TCP socket
+    ///
+    /// Default: IP addresses only, returns error "DNS resolution not available"
+    /// for host names
+    fn resolve_socket_host(&self, host: &str) -> OpFuture<'_, Result<Vec<IpAddr>, String>> {
+        let result = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .map(|ip| vec![ip])
+            .map_err(|_| format!("Cannot resolve {}: DNS resolution not available", host));
+
+        Box::pin(async move { result })
+    }
+
+    /// Handle an outgoing TCP socket connection
+    ///
+    /// `addresses` are the checked addresses of `host`: connect to one of
+    /// them, without resolving `host` again (the name is for TLS).
+    ///
+    /// Default: returns error "Sockets not available"
+    fn handle_socket_connect(
+        &self,
+        _host: &str,
+        _addresses: Vec<SocketAddr>,
+        _tls: SocketTls,
+    ) -> OpFuture<'_, Result<SocketConnection, String>> {
+        Box::pin(async { Err("Sockets not available".into()) })
+    }
+
     /// Handle a log message
     ///
     /// Default: prints to stderr
@@ -353,9 +919,37 @@ pub trait OperationsHandler: Send + Sync {
                 Operation::BindingDatabase { binding, op } => {
                     OperationResult::Database(self.handle_binding_database(&binding, op).await)
                 }
+                Operation::BindingQueue { binding, op } => {
+                    OperationResult::Queue(self.handle_binding_queue(&binding, op).await)
+                }
+                Operation::Cache { cache, op } => {
+                    OperationResult::Cache(self.handle_cache(cache.as_deref(), op).await)
+                }
+                Operation::BindingDurableObject { binding, op } => OperationResult::DurableObject(
+                    self.handle_binding_durable_object(&binding, op).await,
+                ),
+                Operation::DurableStorage(op) => {
+                    OperationResult::DurableStorage(self.handle_durable_storage(op).await)
+                }
+                Operation::BindingEmail { binding, message } => {
+                    OperationResult::Email(self.handle_binding_email(&binding, message).await)
+                }
+                Operation::BindingRateLimit { binding, key, cost } => OperationResult::RateLimit(
+                    self.handle_binding_rate_limit(&binding, &key, cost).await,
+                ),
+                Operation::BindingSecret { binding } => {
+                    OperationResult::Secret(self.handle_binding_secret(&binding).await)
+                }
                 Operation::BindingWorker { binding, request } => {
                     OperationResult::Http(self.handle_binding_worker(&binding, request).await)
                 }
+                Operation::BindingWorkerRpc { binding, call } => {
+                    OperationResult::Rpc(self.handle_binding_worker_rpc(&binding, call).await)
+                }
+                Operation::BindingAnalytics { binding, point } => {
+                    self.handle_binding_analytics(&binding, point);
+                    OperationResult::Ack
+                }
                 Operation::Log { level, message } => {
                     self.handle_log(level, message);
                     OperationResult::Ack
@@ -364,6 +958,29 @@ pub trait OperationsHandler: Send + Sync {
                 Operation::WebSocketConnect { url, headers } => {
                     OperationResult::WebSocket(self.handle_websocket_connect(&url, headers).await)
                 }
+
+                Operation::SocketConnect { host, port, tls } => {
+                    let result = async {
+                        self.check_egress(&host, port)?;
+
+                        let ips = self.resolve_socket_host(&host).await?;
+
+                        if ips.is_empty() {
+                            return Err(format!("No address found for host {}", host));
+                        }
+
+                        // Every address must pass: any of them may be used
+                        for ip in &ips {
+                            self.check_egress_ip(*ip)?;
+                        }
+
+                        let addresses = ips.into_iter().map(|ip| SocketAddr::new(ip, port));
+                        self.handle_socket_connect(&host, addresses.collect(), tls)
+                            .await
+                    };
+
+                    OperationResult::Socket(result.await)
+                }
             }
         })
     }
@@ -386,3 +1003,77 @@ impl OperationsHandler for DefaultOps {}
 // Keep DirectOperations as an alias for backwards compatibility
 #[doc(hidden)]
 pub type DirectOperations = DefaultOps;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn param(value: serde_json::Value) -> SqlParam {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn typed_params_deserialize() {
+        let typed = param(json!({ "type": "bytes", "value": [1, 2] }));
+        assert!(matches!(typed, SqlParam::Typed(_)));
+        assert_eq!(typed.to_value(), SqlValue::Bytes(vec![1, 2]));
+        assert_eq!(typed.type_name(), None);
+
+        let typed = param(json!({ "type": "text", "value": "{}", "typeName": "jsonb" }));
+        assert_eq!(typed.to_value(), SqlValue::Text("{}".into()));
+        assert_eq!(typed.type_name(), Some("jsonb"));
+
+        let typed = param(json!({ "type": "null" }));
+        assert_eq!(typed.to_value(), SqlValue::Null);
+    }
+
+    #[test]
+    fn plain_params_stay_untyped() {
+        for (value, expected) in [
+            (json!(null), SqlValue::Null),
+            (json!(true), SqlValue::Bool(true)),
+            (json!(42), SqlValue::Int(42)),
+            (json!(1.5), SqlValue::Float(1.5)),
+            (json!("bytes"), SqlValue::Text("bytes".into())),
+        ] {
+            let param = param(value);
+            assert!(matches!(param, SqlParam::Primitive(_)));
+            assert_eq!(param.to_value(), expected);
+        }
+
+        let array = param(json!([1, "a", null]));
+        assert!(matches!(array, SqlParam::Array(_)));
+        assert_eq!(
+            array.to_value(),
+            SqlValue::Array(vec![
+                SqlValue::Int(1),
+                SqlValue::Text("a".into()),
+                SqlValue::Null
+            ])
+        );
+
+        // Objects that are not typed values are rejected
+        assert!(serde_json::from_value::<SqlParam>(json!({ "a": 1 })).is_err());
+    }
+
+    #[test]
+    fn params_round_trip() {
+        for value in [
+            json!(7),
+            json!("text"),
+            json!([1.5, false]),
+            json!({ "type": "timestamp", "value": 1_700_000_000_000_000i64 }),
+            json!({ "type": "uuid", "value": "00000000-0000-0000-0000-000000000000", "typeName": "uuid" }),
+            json!({ "type": "array", "value": [{ "type": "int", "value": 1 }] }),
+        ] {
+            let param = param(value.clone());
+            let serialized = serde_json::to_value(&param).unwrap();
+            assert_eq!(serialized, value);
+
+            let decoded: SqlParam = serde_json::from_value(serialized).unwrap();
+            assert_eq!(decoded.to_value(), param.to_value());
+            assert_eq!(decoded.type_name(), param.type_name());
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Queue message types shared by queue operations and queue events
+//!
+//! Producers send `QueueSend`s through `QueueOp`; consumers receive a batch
+//! of `QueueMessage`s in an `Event::Queue` and report one `QueueOutcome` per
+//! message in a `QueueBatchResult`. Field names serialize in camelCase to
+//! match the Cloudflare Queues API.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Message body, tagged with its content type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "contentType", content = "body", rename_all = "lowercase")]
+pub enum QueueBody {
+    /// JSON value (default content type)
+    Json(serde_json::Value),
+    /// UTF-8 text
+    Text(String),
+    /// Raw bytes
+    Bytes(Vec<u8>),
+}
+
+impl QueueBody {
+    /// Size of the encoded body in bytes
+    pub fn byte_size(&self) -> usize {
+        match self {
+            Self::Json(value) => value.to_string().len(),
+            Self::Text(text) => text.len(),
+            Self::Bytes(bytes) => bytes.len(),
+        }
+    }
+}
+
+/// Message to send to a queue (Cloudflare `MessageSendRequest`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSend {
+    /// Message body
+    #[serde(flatten)]
+    pub body: QueueBody,
+    /// Seconds before the message can be delivered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<u32>,
+}
+
+impl QueueSend {
+    /// Message delivered as soon as possible
+    pub fn new(body: QueueBody) -> Self {
+        Self {
+            body,
+            delay_seconds: None,
+        }
+    }
+
+    /// Delay delivery by `seconds`
+    pub fn with_delay(mut self, seconds: u32) -> Self {
+        self.delay_seconds = Some(seconds);
+        self
+    }
+}
+
+/// Message delivered to a queue consumer (Cloudflare `Message`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueMessage {
+    /// Unique message id
+    pub id: String,
+    /// Time the message was sent (Unix timestamp in milliseconds)
+    pub timestamp: u64,
+    /// Message body
+    #[serde(flatten)]
+    pub body: QueueBody,
+    /// Delivery attempt (starts at 1)
+    pub attempts: u32,
+}
+
+/// What to do with a delivered message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QueueOutcome {
+    /// Processed: remove it from the queue
+    Ack,
+    /// Deliver it again, after `delay_seconds` if set
+    Retry {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delay_seconds: Option<u32>,
+    },
+}
+
+/// Result of a queue consumer invocation
+///
+/// Messages without an explicit outcome are acknowledged if the handler
+/// succeeded, and retried if it failed (Cloudflare semantics).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueBatchResult {
+    /// Explicit outcomes (`ack()`, `retry()`, `ackAll()`...), by message id
+    #[serde(default)]
+    pub outcomes: HashMap<String, QueueOutcome>,
+    /// Error thrown by the handler, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl QueueBatchResult {
+    /// Failed invocation: every message without an outcome is retried
+    pub fn err(msg: impl Into<String>) -> Self {
+        Self {
+            outcomes: HashMap::new(),
+            error: Some(msg.into()),
+        }
+    }
+
+    /// Outcome of a message, explicit or implied by the handler result
+    pub fn outcome(&self, id: &str) -> QueueOutcome {
+        match self.outcomes.get(id) {
+            Some(outcome) => *outcome,
+            None if self.error.is_some() => QueueOutcome::Retry {
+                delay_seconds: None,
+            },
+            None => QueueOutcome::Ack,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_serializes_flat() {
+        let send = QueueSend::new(QueueBody::Json(serde_json::json!({ "id": 1 }))).with_delay(30);
+        let json = serde_json::to_value(&send).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "contentType": "json", "body": { "id": 1 }, "delaySeconds": 30 })
+        );
+        assert_eq!(serde_json::from_value::<QueueSend>(json).unwrap(), send);
+    }
+
+    #[test]
+    fn send_round_trips_every_body() {
+        let bodies = [
+            QueueBody::Json(serde_json::json!(["a", null])),
+            QueueBody::Text("hello".into()),
+            QueueBody::Bytes(vec![0, 1, 255]),
+        ];
+
+        for body in bodies {
+            let send = QueueSend::new(body);
+            let json = serde_json::to_string(&send).unwrap();
+
+            assert!(!json.contains("delaySeconds"));
+            assert_eq!(serde_json::from_str::<QueueSend>(&json).unwrap(), send);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Rate limit types shared by rate limit operations
+//!
+//! A rate limit binding counts requests per key (client IP, API token...)
+//! against a limit per period. Each call spends `cost` units for a key and
+//! reports whether the call is allowed, what is left and when the limit
+//! resets. Field names serialize in camelCase.
+
+use serde::{Deserialize, Serialize};
+
+/// Counting algorithm of a rate limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RateLimitAlgorithm {
+    /// Bucket of `limit` tokens refilled continuously over `period_seconds`
+    ///
+    /// Allows bursts up to `limit`, then a steady rate.
+    #[default]
+    TokenBucket,
+    /// Fixed windows of `period_seconds`, with the previous window weighted
+    /// by how much of it still overlaps the sliding period
+    ///
+    /// Smooths window boundaries without storing individual requests.
+    SlidingWindow,
+}
+
+/// Configuration of a rate limit binding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitConfig {
+    /// Units allowed per period
+    pub limit: u64,
+    /// Period in seconds
+    pub period_seconds: u64,
+    /// Counting algorithm
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+}
+
+impl RateLimitConfig {
+    /// Token bucket of `limit` units per `period_seconds`
+    pub fn new(limit: u64, period_seconds: u64) -> Self {
+        Self {
+            limit,
+            period_seconds,
+            algorithm: RateLimitAlgorithm::TokenBucket,
+        }
+    }
+
+    /// Use another counting algorithm
+    pub fn with_algorithm(mut self, algorithm: RateLimitAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Validate the configuration (positive limit and period)
+    pub fn check(&self) -> Result<(), String> {
+        if self.limit == 0 || self.period_seconds == 0 {
+            return Err(format!(
+                "Invalid rate limit: {} per {}s (both must be positive)",
+                self.limit, self.period_seconds
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of a rate limit call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitOutcome {
+    /// Whether the call is within the limit (its cost was spent)
+    pub allowed: bool,
+    /// Units still available for the key
+    pub remaining: u64,
+    /// Seconds until the whole limit is available again
+    pub reset: u64,
+}
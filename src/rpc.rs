@@ -0,0 +1,110 @@
+//! RPC types shared by worker binding calls and RPC events
+//!
+//! An RPC call invokes a named method exported by the target worker with
+//! JSON arguments, instead of going through an `HttpRequest`. The caller
+//! sends an `RpcCall` through `Operation::BindingWorkerRpc`; the callee
+//! receives it in an `Event::Rpc` and returns a JSON value or an `RpcError`.
+//! Field names serialize in camelCase.
+
+use serde::{Deserialize, Serialize};
+
+/// Method call on a worker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCall {
+    /// Name of the method exported by the target worker
+    pub method: String,
+    /// Positional arguments
+    #[serde(default)]
+    pub args: Vec<serde_json::Value>,
+    /// Named entrypoint exporting the method (the default export if `None`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+}
+
+impl RpcCall {
+    pub fn new(method: impl Into<String>, args: Vec<serde_json::Value>) -> Self {
+        Self {
+            method: method.into(),
+            args,
+            entrypoint: None,
+        }
+    }
+
+    /// Call the method on a named entrypoint instead of the default export
+    pub fn with_entrypoint(mut self, entrypoint: impl Into<String>) -> Self {
+        self.entrypoint = Some(entrypoint.into());
+        self
+    }
+}
+
+/// Error thrown by an RPC method (or raised while delivering the call)
+///
+/// Rethrown on the caller side as a JS error with the same name and message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcError {
+    /// JS error name ("Error", "TypeError", ...)
+    pub name: String,
+    /// Error message
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+/// Plain `Error` with a message
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new("Error", message)
+    }
+}
+
+impl From<&str> for RpcError {
+    fn from(message: &str) -> Self {
+        Self::new("Error", message)
+    }
+}
+
+/// Result of an RPC call: the returned value or the thrown error
+pub type RpcResult = Result<serde_json::Value, RpcError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn call_round_trip() {
+        let call = RpcCall::new("add", vec![json!(1), json!(2)]);
+        let value = serde_json::to_value(&call).unwrap();
+        assert_eq!(value, json!({ "method": "add", "args": [1, 2] }));
+        assert_eq!(serde_json::from_value::<RpcCall>(value).unwrap(), call);
+
+        let call = call.with_entrypoint("Admin");
+        let value = serde_json::to_value(&call).unwrap();
+        assert_eq!(
+            value,
+            json!({ "method": "add", "args": [1, 2], "entrypoint": "Admin" })
+        );
+        assert_eq!(serde_json::from_value::<RpcCall>(value).unwrap(), call);
+
+        // Arguments and entrypoint are optional
+        let call: RpcCall = serde_json::from_value(json!({ "method": "ping" })).unwrap();
+        assert_eq!(call, RpcCall::new("ping", vec![]));
+    }
+}
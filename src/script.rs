@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use crate::{EmailPolicy, RateLimitConfig, Secret};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// Worker code - JavaScript source, WebAssembly binary, or pre-compiled snapshot
 #[derive(Debug, Clone)]
@@ -93,7 +95,8 @@ impl From<Vec<u8>> for WorkerCode {
 }
 
 /// Type of binding (resource type)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BindingType {
     /// Static assets (S3/R2 read-only)
     Assets,
@@ -105,15 +108,140 @@ pub enum BindingType {
     Database,
     /// Worker-to-worker binding
     Worker,
+    /// Message queue (producer side)
+    Queue,
+    /// Durable object namespace (stateful actors)
+    DurableObject,
+    /// Secret fetched on first use (value never embedded in the script)
+    Secret,
+    /// Rate limiter (requests per key)
+    RateLimit,
+    /// Analytics dataset (write-only data points)
+    Analytics,
+    /// Outbound email (send only)
+    Email,
+}
+
+/// How an assets binding answers paths without a matching asset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotFoundHandling {
+    /// Plain 404 response
+    #[default]
+    None,
+    /// Serve `/index.html` (client-side routing)
+    SinglePageApplication,
+    /// Serve the nearest `404.html` with a 404 status
+    #[serde(rename = "404-page")]
+    NotFoundPage,
+}
+
+/// Typed configuration of a binding (no credentials)
+///
+/// The variant must match the binding type; bindings without configuration
+/// use the runtime defaults. Serializes with a `type` tag, fields in
+/// camelCase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum BindingConfig {
+    Assets {
+        #[serde(default)]
+        not_found_handling: NotFoundHandling,
+        /// Content type of assets with an unknown extension
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default_content_type: Option<String>,
+    },
+    Storage {
+        #[serde(default)]
+        read_only: bool,
+        /// Data location constraint ("eu", ...)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        jurisdiction: Option<String>,
+    },
+    Kv {
+        #[serde(default)]
+        read_only: bool,
+    },
+    Database {
+        #[serde(default)]
+        read_only: bool,
+    },
+    Worker {
+        /// Named export of the target worker (`None` = default export)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entrypoint: Option<String>,
+    },
+    DurableObject {
+        /// Class exported by the worker implementing the objects
+        class_name: String,
+        /// Data location constraint ("eu", ...)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        jurisdiction: Option<String>,
+    },
+    RateLimit(RateLimitConfig),
+    Email(EmailPolicy),
+}
+
+impl BindingConfig {
+    /// Binding type this configuration applies to
+    pub fn binding_type(&self) -> BindingType {
+        match self {
+            Self::Assets { .. } => BindingType::Assets,
+            Self::Storage { .. } => BindingType::Storage,
+            Self::Kv { .. } => BindingType::Kv,
+            Self::Database { .. } => BindingType::Database,
+            Self::Worker { .. } => BindingType::Worker,
+            Self::DurableObject { .. } => BindingType::DurableObject,
+            Self::RateLimit(_) => BindingType::RateLimit,
+            Self::Email(_) => BindingType::Email,
+        }
+    }
+
+    /// Whether writes through the binding are rejected
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Assets { .. } => true,
+            Self::Storage { read_only, .. }
+            | Self::Kv { read_only }
+            | Self::Database { read_only } => *read_only,
+            _ => false,
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match self {
+            Self::Assets {
+                default_content_type: Some(content_type),
+                ..
+            } if !content_type.contains('/') => {
+                Err(format!("Invalid default content type: {}", content_type))
+            }
+            Self::Worker {
+                entrypoint: Some(entrypoint),
+            } => check_identifier("entrypoint", entrypoint),
+            Self::DurableObject { class_name, .. } => check_identifier("class name", class_name),
+            Self::RateLimit(config) => config.check(),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Binding info passed to the runtime (name + type, no credentials)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BindingInfo {
     /// Binding name as it appears in JS (e.g., "ASSETS", "MY_BUCKET")
     pub name: String,
     /// Type of binding
+    #[serde(rename = "type")]
     pub binding_type: BindingType,
+    /// Binding configuration (`None` = runtime defaults)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<BindingConfig>,
 }
 
 impl BindingInfo {
@@ -121,9 +249,38 @@ impl BindingInfo {
         Self {
             name: name.into(),
             binding_type,
+            config: None,
         }
     }
 
+    /// Attach a configuration to the binding
+    pub fn with_config(mut self, config: BindingConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Validate the name (JS identifier) and the configuration
+    pub fn validate(&self) -> Result<(), String> {
+        check_identifier("binding name", &self.name)?;
+
+        let Some(config) = &self.config else {
+            return Ok(());
+        };
+
+        if config.binding_type() != self.binding_type {
+            return Err(format!(
+                "Binding {}: {:?} configuration on a {:?} binding",
+                self.name,
+                config.binding_type(),
+                self.binding_type
+            ));
+        }
+
+        config
+            .check()
+            .map_err(|e| format!("Binding {}: {}", self.name, e))
+    }
+
     pub fn assets(name: impl Into<String>) -> Self {
         Self::new(name, BindingType::Assets)
     }
@@ -143,13 +300,40 @@ impl BindingInfo {
     pub fn worker(name: impl Into<String>) -> Self {
         Self::new(name, BindingType::Worker)
     }
+
+    pub fn queue(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::Queue)
+    }
+
+    pub fn durable_object(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::DurableObject)
+    }
+
+    pub fn secret(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::Secret)
+    }
+
+    pub fn rate_limit(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::RateLimit)
+    }
+
+    pub fn analytics(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::Analytics)
+    }
+
+    pub fn email(name: impl Into<String>) -> Self {
+        Self::new(name, BindingType::Email)
+    }
 }
 
 /// Script with code, environment variables, and bindings
 #[derive(Debug, Clone)]
 pub struct Script {
     pub code: WorkerCode,
+    /// Plain configuration (printed by `Debug`: put secrets in `secrets`)
     pub env: Option<HashMap<String, String>>,
+    /// Secrets embedded in the script (redacted by `Debug`)
+    pub secrets: HashMap<String, Secret>,
     /// Bindings available to the worker (names + types only, no credentials)
     pub bindings: Vec<BindingInfo>,
 }
@@ -160,6 +344,7 @@ impl Script {
         Self {
             code: code.into(),
             env: None,
+            secrets: HashMap::new(),
             bindings: Vec::new(),
         }
     }
@@ -169,6 +354,7 @@ impl Script {
         Self {
             code: code.into(),
             env: Some(env),
+            secrets: HashMap::new(),
             bindings: Vec::new(),
         }
     }
@@ -182,6 +368,7 @@ impl Script {
         Self {
             code: code.into(),
             env,
+            secrets: HashMap::new(),
             bindings,
         }
     }
@@ -191,4 +378,361 @@ impl Script {
         self.bindings.push(binding);
         self
     }
+
+    /// Add a secret to the script
+    pub fn add_secret(mut self, name: impl Into<String>, value: impl Into<Secret>) -> Self {
+        self.secrets.insert(name.into(), value.into());
+        self
+    }
+
+    /// Validate the bindings before creating a worker
+    ///
+    /// Every binding must be valid and its name unique: bindings, env vars
+    /// and secrets share the `env` object in JS.
+    pub fn validate_bindings(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+
+        for binding in &self.bindings {
+            binding.validate()?;
+
+            if !names.insert(binding.name.as_str()) {
+                return Err(format!("Duplicate binding name: {}", binding.name));
+            }
+
+            let in_env = self
+                .env
+                .as_ref()
+                .is_some_and(|env| env.contains_key(&binding.name));
+
+            if in_env || self.secrets.contains_key(&binding.name) {
+                return Err(format!(
+                    "Binding name {} conflicts with a variable",
+                    binding.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reserved words that cannot be used as identifiers (strict mode)
+const RESERVED_WORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+fn check_identifier(what: &str, name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        && !RESERVED_WORDS.contains(&name);
+
+    if !valid {
+        return Err(format!(
+            "Invalid {}: {:?} is not a JS identifier",
+            what, name
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RateLimitAlgorithm;
+    use serde_json::json;
+
+    #[test]
+    fn identifiers() {
+        for name in [
+            "ASSETS",
+            "my_bucket",
+            "_private",
+            "$store",
+            "kv2",
+            "Équipe",
+            "letter",
+        ] {
+            assert_eq!(check_identifier("name", name), Ok(()), "{}", name);
+        }
+
+        for name in [
+            "",
+            "2KV",
+            "MY-BUCKET",
+            "my bucket",
+            "a.b",
+            "kv\n",
+            "ünïcode-",
+        ] {
+            assert!(check_identifier("name", name).is_err(), "{:?}", name);
+        }
+
+        for word in RESERVED_WORDS {
+            let err = check_identifier("binding name", word).unwrap_err();
+            assert!(err.starts_with("Invalid binding name"), "{}", err);
+        }
+
+        // Reserved words are case-sensitive
+        assert_eq!(check_identifier("name", "Class"), Ok(()));
+    }
+
+    #[test]
+    fn validates_bindings() {
+        let script = Script::new("")
+            .add_binding(BindingInfo::kv("KV"))
+            .add_binding(BindingInfo::storage("BUCKET"));
+        assert_eq!(script.validate_bindings(), Ok(()));
+
+        let script = script.add_binding(BindingInfo::assets("class"));
+        assert!(script.validate_bindings().is_err());
+
+        let binding =
+            BindingInfo::kv("KV").with_config(BindingConfig::Database { read_only: true });
+        let err = binding.validate().unwrap_err();
+        assert_eq!(err, "Binding KV: Database configuration on a Kv binding");
+
+        let binding =
+            BindingInfo::durable_object("COUNTER").with_config(BindingConfig::DurableObject {
+                class_name: "my-counter".into(),
+                jurisdiction: None,
+            });
+        assert!(binding.validate().is_err());
+
+        let binding = BindingInfo::rate_limit("LIMITER")
+            .with_config(BindingConfig::RateLimit(RateLimitConfig::new(0, 60)));
+        assert!(
+            binding
+                .validate()
+                .unwrap_err()
+                .starts_with("Binding LIMITER")
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_binding_names() {
+        let script = Script::new("")
+            .add_binding(BindingInfo::kv("STORE"))
+            .add_binding(BindingInfo::storage("STORE"));
+
+        assert_eq!(
+            script.validate_bindings(),
+            Err("Duplicate binding name: STORE".into())
+        );
+    }
+
+    #[test]
+    fn rejects_names_of_variables() {
+        let env = HashMap::from([("API_URL".to_string(), "https://api.example".to_string())]);
+
+        let script = Script::with_env("", env.clone()).add_binding(BindingInfo::worker("API_URL"));
+        assert_eq!(
+            script.validate_bindings(),
+            Err("Binding name API_URL conflicts with a variable".into())
+        );
+
+        let script = Script::new("")
+            .add_secret("API_KEY", "secret")
+            .add_binding(BindingInfo::secret("API_KEY"));
+        assert_eq!(
+            script.validate_bindings(),
+            Err("Binding name API_KEY conflicts with a variable".into())
+        );
+
+        let script = Script::with_env("", env)
+            .add_secret("API_KEY", "secret")
+            .add_binding(BindingInfo::kv("KV"));
+        assert_eq!(script.validate_bindings(), Ok(()));
+    }
+
+    #[test]
+    fn binding_config_serde() {
+        let cases = [
+            (
+                BindingConfig::Assets {
+                    not_found_handling: NotFoundHandling::SinglePageApplication,
+                    default_content_type: Some("text/plain".into()),
+                },
+                json!({
+                    "type": "assets",
+                    "notFoundHandling": "single-page-application",
+                    "defaultContentType": "text/plain"
+                }),
+            ),
+            (
+                BindingConfig::Assets {
+                    not_found_handling: NotFoundHandling::NotFoundPage,
+                    default_content_type: None,
+                },
+                json!({ "type": "assets", "notFoundHandling": "404-page" }),
+            ),
+            (
+                BindingConfig::Storage {
+                    read_only: true,
+                    jurisdiction: Some("eu".into()),
+                },
+                json!({ "type": "storage", "readOnly": true, "jurisdiction": "eu" }),
+            ),
+            (
+                BindingConfig::Kv { read_only: false },
+                json!({ "type": "kv", "readOnly": false }),
+            ),
+            (
+                BindingConfig::Database { read_only: true },
+                json!({ "type": "database", "readOnly": true }),
+            ),
+            (
+                BindingConfig::Worker {
+                    entrypoint: Some("Admin".into()),
+                },
+                json!({ "type": "worker", "entrypoint": "Admin" }),
+            ),
+            (
+                BindingConfig::DurableObject {
+                    class_name: "Counter".into(),
+                    jurisdiction: None,
+                },
+                json!({ "type": "durableObject", "className": "Counter" }),
+            ),
+            (
+                BindingConfig::RateLimit(
+                    RateLimitConfig::new(100, 60).with_algorithm(RateLimitAlgorithm::SlidingWindow),
+                ),
+                json!({
+                    "type": "rateLimit",
+                    "limit": 100,
+                    "periodSeconds": 60,
+                    "algorithm": "slidingWindow"
+                }),
+            ),
+            (
+                BindingConfig::Email(
+                    EmailPolicy::default().with_senders(vec!["noreply@shop.example".into()]),
+                ),
+                json!({
+                    "type": "email",
+                    "allowedSenders": ["noreply@shop.example"],
+                    "allowedRecipients": null,
+                    "maxMessageSize": 25 * 1024 * 1024
+                }),
+            ),
+        ];
+
+        for (config, value) in cases {
+            assert_eq!(serde_json::to_value(&config).unwrap(), value);
+            assert_eq!(
+                serde_json::from_value::<BindingConfig>(value).unwrap(),
+                config
+            );
+        }
+    }
+
+    #[test]
+    fn binding_config_defaults() {
+        let parse = |value| serde_json::from_value::<BindingConfig>(value).unwrap();
+
+        assert_eq!(
+            parse(json!({ "type": "assets" })),
+            BindingConfig::Assets {
+                not_found_handling: NotFoundHandling::None,
+                default_content_type: None,
+            }
+        );
+        assert_eq!(
+            parse(json!({ "type": "kv" })),
+            BindingConfig::Kv { read_only: false }
+        );
+        assert_eq!(
+            parse(json!({ "type": "rateLimit", "limit": 10, "periodSeconds": 1 })),
+            BindingConfig::RateLimit(RateLimitConfig::new(10, 1))
+        );
+        assert_eq!(
+            parse(json!({ "type": "email" })),
+            BindingConfig::Email(EmailPolicy::default())
+        );
+
+        assert!(serde_json::from_value::<BindingConfig>(json!({ "type": "queue" })).is_err());
+        assert!(
+            serde_json::from_value::<BindingConfig>(json!({ "type": "durableObject" })).is_err()
+        );
+    }
+
+    #[test]
+    fn binding_info_serde() {
+        let binding = BindingInfo::rate_limit("LIMITER")
+            .with_config(BindingConfig::RateLimit(RateLimitConfig::new(5, 10)));
+        let value = json!({
+            "name": "LIMITER",
+            "type": "rateLimit",
+            "config": {
+                "type": "rateLimit",
+                "limit": 5,
+                "periodSeconds": 10,
+                "algorithm": "tokenBucket"
+            }
+        });
+
+        assert_eq!(serde_json::to_value(&binding).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<BindingInfo>(value).unwrap(),
+            binding
+        );
+
+        let binding = BindingInfo::analytics("EVENTS");
+        let value = json!({ "name": "EVENTS", "type": "analytics" });
+        assert_eq!(serde_json::to_value(&binding).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<BindingInfo>(value).unwrap(),
+            binding
+        );
+    }
 }
@@ -0,0 +1,90 @@
+//! Secret values kept apart from plain configuration
+//!
+//! A `Secret` never shows its value through `Debug` or `Display`, so scripts
+//! and results can be logged safely, and its memory is zeroized on drop.
+//! Secrets are either embedded in `Script::secrets` or fetched on first use
+//! through `Operation::BindingSecret`, letting the runner audit each access.
+
+use zeroize::Zeroize;
+
+/// Secret string, redacted when formatted and zeroized on drop
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Secret value (avoid copying it into long-lived strings)
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl std::fmt::Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Script;
+
+    const VALUE: &str = "sk-live-1234";
+
+    #[test]
+    fn formatting_redacts_value() {
+        let secret = Secret::new(VALUE);
+
+        for formatted in [
+            format!("{:?}", secret),
+            format!("{:#?}", secret),
+            format!("{}", secret),
+            format!("{:>20}", secret),
+        ] {
+            assert!(!formatted.contains(VALUE), "{}", formatted);
+            assert!(formatted.contains("[REDACTED]"));
+        }
+
+        assert_eq!(secret.expose(), VALUE);
+    }
+
+    #[test]
+    fn script_debug_redacts_secrets() {
+        let script = Script::new("export default {}")
+            .add_secret("API_KEY", VALUE)
+            .add_secret("TOKEN", VALUE.to_string());
+
+        for formatted in [format!("{:?}", script), format!("{:#?}", script)] {
+            assert!(!formatted.contains(VALUE), "{}", formatted);
+            assert!(formatted.contains("API_KEY"));
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! TCP socket types for outgoing connections (`connect()` API).
+//!
+//! Like WebSockets, the TCP connection lives in the runner; the runtime
+//! exchanges bytes with it through bounded channels. `EgressPolicy` is the
+//! default policy of `OperationsHandler::check_egress` and
+//! `OperationsHandler::check_egress_ip`, which check the requested host and
+//! every resolved address before each connection.
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tokio::sync::mpsc;
+
+/// Transport security of a socket (`secureTransport`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketTls {
+    /// Plain TCP
+    #[default]
+    Off,
+    /// TLS from the start
+    On,
+    /// Plain TCP, upgradable with `SocketOutgoing::StartTls`
+    StartTls,
+}
+
+/// Message sent from JS to the socket
+#[derive(Debug)]
+pub enum SocketOutgoing {
+    /// Bytes to write
+    Data(Bytes),
+    /// Upgrade to TLS (only for `SocketTls::StartTls` connections)
+    StartTls,
+    /// Close the write side of the connection
+    Close,
+}
+
+/// Message received from the socket, delivered to JS
+#[derive(Debug)]
+pub enum SocketIncoming {
+    /// Bytes read
+    Data(Bytes),
+    /// TLS handshake completed after `SocketOutgoing::StartTls`
+    TlsStarted,
+    /// Connection closed by the remote end
+    Closed,
+    /// Error on the connection
+    Error(String),
+}
+
+/// Runner side of a socket: the counterpart of `SocketConnection`
+pub struct SocketEndpoint {
+    /// Messages from JS (writes, TLS upgrade, close)
+    pub send_rx: mpsc::Receiver<SocketOutgoing>,
+    /// Messages to JS (reads, TLS started, close, errors)
+    pub recv_tx: mpsc::Sender<SocketIncoming>,
+}
+
+/// Established socket connection handle.
+///
+/// Returned by [`crate::OperationsHandler::handle_socket_connect`].
+/// The runner owns the actual TCP/TLS connection; this struct provides
+/// bounded channels for bidirectional byte passing.
+pub struct SocketConnection {
+    /// Send bytes to the remote end (JS -> socket)
+    pub send_tx: mpsc::Sender<SocketOutgoing>,
+    /// Receive bytes from the remote end (socket -> JS)
+    pub recv_rx: mpsc::Receiver<SocketIncoming>,
+    /// Remote address the socket is connected to, if known
+    pub remote_address: Option<String>,
+}
+
+impl std::fmt::Debug for SocketConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SocketConnection")
+            .field("remote_address", &self.remote_address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SocketConnection {
+    /// Create a connection handle and the runner endpoint used to serve it
+    pub fn channel(buffer_size: usize) -> (Self, SocketEndpoint) {
+        let (send_tx, send_rx) = mpsc::channel(buffer_size);
+        let (recv_tx, recv_rx) = mpsc::channel(buffer_size);
+
+        (
+            Self {
+                send_tx,
+                recv_rx,
+                remote_address: None,
+            },
+            SocketEndpoint { send_rx, recv_tx },
+        )
+    }
+}
+
+/// Reference egress policy for outgoing sockets
+///
+/// Host entries are exact names (`db.example.com`) or subdomain wildcards
+/// (`*.example.com`), compared case-insensitively. Numeric hosts must be
+/// plain IP addresses (`127.1` or `0x7f000001` are rejected). `check_ip`
+/// validates resolved addresses, so that names resolving to private networks
+/// are rejected too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressPolicy {
+    /// Only these hosts can be reached (`None` = any)
+    #[serde(default)]
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Hosts that can never be reached
+    #[serde(default)]
+    pub blocked_hosts: Vec<String>,
+    /// Ports that can never be reached (default: 25, SMTP)
+    #[serde(default)]
+    pub blocked_ports: Vec<u16>,
+    /// Allow loopback, private, link-local and other non-public addresses
+    #[serde(default)]
+    pub allow_private: bool,
+}
+
+impl Default for EgressPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: None,
+            blocked_hosts: Vec::new(),
+            blocked_ports: vec![25],
+            allow_private: false,
+        }
+    }
+}
+
+impl EgressPolicy {
+    /// Validate a connection target before connecting
+    pub fn check(&self, host: &str, port: u16) -> Result<(), String> {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.');
+
+        if host.is_empty() || port == 0 {
+            return Err(format!("Invalid address: {}:{}", host, port));
+        }
+
+        if self.blocked_ports.contains(&port) {
+            return Err(format!("Port {} is blocked", port));
+        }
+
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            self.check_ip(ip)?;
+        } else if is_numeric(host) {
+            // Resolvers read these as IPv4 addresses in other notations
+            return Err(format!(
+                "Invalid address: {} (not a dotted-quad address)",
+                host
+            ));
+        } else if !self.allow_private
+            && (host.eq_ignore_ascii_case("localhost")
+                || host.to_ascii_lowercase().ends_with(".localhost"))
+        {
+            return Err(format!("Host {} is not public", host));
+        }
+
+        if self
+            .blocked_hosts
+            .iter()
+            .any(|entry| host_matches(entry, host))
+        {
+            return Err(format!("Host {} is blocked", host));
+        }
+
+        if let Some(allowed) = &self.allowed_hosts
+            && !allowed.iter().any(|entry| host_matches(entry, host))
+        {
+            return Err(format!("Host {} is not allowed", host));
+        }
+
+        Ok(())
+    }
+
+    /// Validate a resolved address (rejects non-public ones unless allowed)
+    pub fn check_ip(&self, ip: IpAddr) -> Result<(), String> {
+        if !self.allow_private && !is_public(ip) {
+            return Err(format!("Address {} is not public", ip));
+        }
+
+        Ok(())
+    }
+}
+
+fn host_matches(entry: &str, host: &str) -> bool {
+    match entry.strip_prefix("*.") {
+        Some(domain) => {
+            let (host, domain) = (host.as_bytes(), domain.as_bytes());
+
+            host.len() > domain.len() + 1
+                && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+                && host[host.len() - domain.len() - 1] == b'.'
+        }
+        None => entry.eq_ignore_ascii_case(host),
+    }
+}
+
+/// Whether a host that is not an IP address would still be read as an IPv4
+/// address (last label in decimal or hex, as in `127.1` or `0x7f000001`)
+fn is_numeric(host: &str) -> bool {
+    let last = host.rsplit('.').next().unwrap_or_default();
+
+    let hex = last
+        .strip_prefix("0x")
+        .or_else(|| last.strip_prefix("0X"))
+        .is_some_and(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()));
+
+    hex || (!last.is_empty() && last.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+
+            !(ip.is_unspecified()
+                // "This network" (0.0.0.0/8)
+                || a == 0
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // Shared address space (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b))
+                // Reserved (240.0.0.0/4)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(ip));
+            }
+
+            let segments = ip.segments();
+            let first = segments[0];
+
+            // IPv4-compatible (::/96, including :: and ::1) and NAT64
+            // (64:ff9b::/96) addresses reach the embedded IPv4 address
+            if segments[..6] == [0; 6] || segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., a, b, c, d] = ip.octets();
+                return is_public(IpAddr::V4([a, b, c, d].into()));
+            }
+
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local (fc00::/7)
+                || (first & 0xfe00) == 0xfc00
+                // Link-local (fe80::/10)
+                || (first & 0xffc0) == 0xfe80
+                // Local-use NAT64 (64:ff9b:1::/48)
+                || segments[..3] == [0x64, 0xff9b, 1])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OpFuture, Operation, OperationResult, OperationsHandler};
+    use std::net::SocketAddr;
+
+    #[test]
+    fn rejects_numeric_hosts_in_other_notations() {
+        let policy = EgressPolicy {
+            allow_private: true,
+            ..EgressPolicy::default()
+        };
+
+        for host in ["127.1", "2130706433", "0x7f000001", "0x7F.1", "1.2.3.0x4"] {
+            assert!(policy.check(host, 80).is_err(), "{}", host);
+        }
+
+        for host in ["127.0.0.1", "example.com", "1.example", "0x.example.com"] {
+            assert!(policy.check(host, 80).is_ok(), "{}", host);
+        }
+    }
+
+    #[test]
+    fn rejects_non_public_addresses() {
+        let policy = EgressPolicy::default();
+
+        for host in [
+            "0.1.2.3",
+            "127.0.0.1",
+            "10.0.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "[::1]",
+            "::127.0.0.1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b:1::1",
+            "fd00::1",
+            "localhost",
+        ] {
+            assert!(policy.check(host, 80).is_err(), "{}", host);
+        }
+
+        for host in ["93.184.216.34", "64:ff9b::5db8:d822", "2606:4700::1111"] {
+            assert!(policy.check(host, 80).is_ok(), "{}", host);
+        }
+
+        assert!(policy.check("example.com", 25).is_err());
+    }
+
+    /// Resolves every name to a fixed address and accepts every connection
+    struct Resolver(IpAddr);
+
+    impl OperationsHandler for Resolver {
+        fn resolve_socket_host(&self, _host: &str) -> OpFuture<'_, Result<Vec<IpAddr>, String>> {
+            let ip = self.0;
+            Box::pin(async move { Ok(vec![ip]) })
+        }
+
+        fn handle_socket_connect(
+            &self,
+            _host: &str,
+            addresses: Vec<SocketAddr>,
+            _tls: SocketTls,
+        ) -> OpFuture<'_, Result<SocketConnection, String>> {
+            let (mut connection, _) = SocketConnection::channel(1);
+            connection.remote_address = Some(addresses[0].to_string());
+            Box::pin(async move { Ok(connection) })
+        }
+    }
+
+    async fn connect(ops: &dyn OperationsHandler, host: &str) -> Result<SocketConnection, String> {
+        let op = Operation::SocketConnect {
+            host: host.into(),
+            port: 443,
+            tls: SocketTls::On,
+        };
+
+        match ops.handle(op).await {
+            OperationResult::Socket(result) => result,
+            _ => panic!("expected a socket result"),
+        }
+    }
+
+    #[tokio::test]
+    async fn handle_checks_resolved_addresses() {
+        let public = Resolver("93.184.216.34".parse().unwrap());
+        let connection = connect(&public, "example.com").await.unwrap();
+        assert_eq!(
+            connection.remote_address.as_deref(),
+            Some("93.184.216.34:443")
+        );
+
+        // DNS rebinding to a private network
+        let private = Resolver("127.0.0.1".parse().unwrap());
+        let err = connect(&private, "rebind.example.com").await.unwrap_err();
+        assert_eq!(err, "Address 127.0.0.1 is not public");
+
+        assert!(connect(&public, "localhost").await.is_err());
+    }
+}
@@ -0,0 +1,641 @@
+//! Storage object types shared by storage operations
+//!
+//! Metadata, streaming bodies, multipart parts, and read options (ranges
+//! and conditional headers) used by `StorageOp` and `StorageResult`.
+//! Field names serialize in camelCase to match the R2 binding API.
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+use tokio::sync::mpsc;
+
+/// Streaming object body with declared length
+///
+/// Same channel shape as `RequestBody::Stream`: a bounded receiver yielding
+/// chunks, so large objects flow through without being buffered.
+pub struct StorageStream {
+    /// Total body size in bytes, if known up front
+    pub content_length: Option<u64>,
+    /// Body chunks (bounded channel for backpressure)
+    pub body: mpsc::Receiver<Result<Bytes, String>>,
+}
+
+impl std::fmt::Debug for StorageStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.content_length {
+            Some(len) => write!(f, "StorageStream({} bytes)", len),
+            None => write!(f, "StorageStream(...)"),
+        }
+    }
+}
+
+impl StorageStream {
+    /// Create a stream and the sender used to feed it
+    pub fn channel(
+        content_length: Option<u64>,
+        buffer_size: usize,
+    ) -> (mpsc::Sender<Result<Bytes, String>>, Self) {
+        let (tx, rx) = mpsc::channel(buffer_size);
+
+        (
+            tx,
+            Self {
+                content_length,
+                body: rx,
+            },
+        )
+    }
+
+    /// Create a single-chunk stream from buffered bytes
+    pub fn from_bytes(bytes: Bytes) -> Self {
+        let (tx, stream) = Self::channel(Some(bytes.len() as u64), 1);
+
+        if !bytes.is_empty() {
+            // Cannot fail: the channel is empty and the receiver is alive
+            let _ = tx.try_send(Ok(bytes));
+        }
+
+        stream
+    }
+
+    /// Collect all chunks, consuming the stream
+    ///
+    /// Fails on the first chunk error, or if the received size does not
+    /// match `content_length`.
+    pub async fn collect(mut self) -> Result<Vec<u8>, String> {
+        let mut body = Vec::with_capacity(self.content_length.unwrap_or(0).min(1 << 20) as usize);
+
+        while let Some(chunk) = self.body.recv().await {
+            body.extend_from_slice(&chunk?);
+        }
+
+        match self.content_length {
+            Some(len) if len != body.len() as u64 => Err(format!(
+                "Body length mismatch: declared {} bytes, received {}",
+                len,
+                body.len()
+            )),
+            _ => Ok(body),
+        }
+    }
+}
+
+/// Standard HTTP headers stored with an object (R2 `httpMetadata`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_disposition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<String>,
+    /// Unix timestamp (ms) sent as `Expires` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_expiry: Option<u64>,
+}
+
+impl HttpMetadata {
+    /// Read metadata from request headers (case-insensitive names)
+    pub fn from_headers(headers: &HashMap<String, String>) -> Self {
+        let get = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        };
+
+        Self {
+            content_type: get("content-type"),
+            content_language: get("content-language"),
+            content_disposition: get("content-disposition"),
+            content_encoding: get("content-encoding"),
+            cache_control: get("cache-control"),
+            cache_expiry: None,
+        }
+    }
+
+    /// Header pairs for the fields that are set (except `cache_expiry`)
+    pub fn to_headers(&self) -> Vec<(String, String)> {
+        [
+            ("content-type", &self.content_type),
+            ("content-language", &self.content_language),
+            ("content-disposition", &self.content_disposition),
+            ("content-encoding", &self.content_encoding),
+            ("cache-control", &self.cache_control),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.clone()?)))
+        .collect()
+    }
+}
+
+/// Object checksums, hex-encoded (R2 `checksums`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checksums {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha384: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha512: Option<String>,
+}
+
+impl Checksums {
+    /// Check if no checksum is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Metadata of a stored object (R2 `R2Object` without body)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMetadata {
+    /// Object key
+    pub key: String,
+    /// Body size in bytes
+    pub size: u64,
+    /// Entity tag (unquoted)
+    pub etag: String,
+    /// Unix timestamp (ms) of the upload
+    pub uploaded: u64,
+    /// Standard HTTP headers
+    #[serde(default)]
+    pub http_metadata: HttpMetadata,
+    /// User-defined key/value pairs
+    #[serde(default)]
+    pub custom_metadata: HashMap<String, String>,
+    /// Checksums supplied (and verified) on upload
+    #[serde(default, skip_serializing_if = "Checksums::is_empty")]
+    pub checksums: Checksums,
+}
+
+impl ObjectMetadata {
+    /// Entity tag in quoted form, for the `ETag` header
+    pub fn http_etag(&self) -> String {
+        format!("\"{}\"", self.etag)
+    }
+}
+
+/// Options for put operations (R2 `R2PutOptions`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PutOptions {
+    /// Standard HTTP headers to store with the object
+    #[serde(default)]
+    pub http_metadata: HttpMetadata,
+    /// User-defined key/value pairs
+    #[serde(default)]
+    pub custom_metadata: HashMap<String, String>,
+    /// Expected checksums of the body, verified by the backend (a mismatch
+    /// fails the put)
+    #[serde(default)]
+    pub checksums: Checksums,
+}
+
+/// Stored object with its metadata
+///
+/// The body is buffered (`Vec<u8>`) for `Get`, and a [`StorageStream`] for
+/// `GetStream`.
+#[derive(Debug)]
+pub struct StorageObject<B = Vec<u8>> {
+    pub metadata: ObjectMetadata,
+    /// Returned byte range, if the get requested one
+    pub range: Option<ContentRange>,
+    pub body: B,
+}
+
+/// Highest part number of a multipart upload (parts are numbered from 1)
+pub const MULTIPART_MAX_PARTS: u16 = 10_000;
+
+/// Minimum size in bytes of every part of a multipart upload but the last
+pub const MULTIPART_MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Part uploaded as part of a multipart upload (R2 `R2UploadedPart`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedPart {
+    /// Part number (1 to `MULTIPART_MAX_PARTS`)
+    pub part_number: u16,
+    /// Entity tag of the part, to pass back on completion
+    pub etag: String,
+}
+
+/// Requested byte range of an object (R2 `R2Range`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StorageRange {
+    /// `length` bytes starting at `offset` (to the end if `length` is None)
+    Offset { offset: u64, length: Option<u64> },
+    /// Last `suffix` bytes
+    Suffix { suffix: u64 },
+}
+
+impl StorageRange {
+    /// Parse a single-range `Range` header (`bytes=0-499`, `bytes=500-`, `bytes=-500`)
+    ///
+    /// Multiple ranges are not supported and yield None.
+    pub fn from_header(value: &str) -> Option<Self> {
+        let spec = value.trim().strip_prefix("bytes=")?.trim();
+
+        if spec.contains(',') {
+            return None;
+        }
+
+        let (start, end) = spec.split_once('-')?;
+        let (start, end) = (start.trim(), end.trim());
+
+        if start.is_empty() {
+            return Some(Self::Suffix {
+                suffix: end.parse().ok()?,
+            });
+        }
+
+        let offset: u64 = start.parse().ok()?;
+
+        let length = if end.is_empty() {
+            None
+        } else {
+            let end: u64 = end.parse().ok()?;
+            Some(end.checked_sub(offset)? + 1)
+        };
+
+        Some(Self::Offset { offset, length })
+    }
+
+    /// Resolve against an object size, None if not satisfiable
+    pub fn resolve(&self, size: u64) -> Option<ContentRange> {
+        let (offset, length) = match *self {
+            Self::Offset { offset, length } => {
+                if offset >= size {
+                    return None;
+                }
+
+                let available = size - offset;
+                (offset, length.map_or(available, |len| len.min(available)))
+            }
+            Self::Suffix { suffix } => {
+                let length = suffix.min(size);
+                (size - length, length)
+            }
+        };
+
+        if length == 0 {
+            return None;
+        }
+
+        Some(ContentRange {
+            offset,
+            length,
+            size,
+        })
+    }
+}
+
+/// Resolved byte range of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentRange {
+    /// First byte returned
+    pub offset: u64,
+    /// Number of bytes returned
+    pub length: u64,
+    /// Total object size
+    pub size: u64,
+}
+
+impl ContentRange {
+    /// Value of the `Content-Range` header (`bytes 0-499/1234`)
+    pub fn header_value(&self) -> String {
+        format!(
+            "bytes {}-{}/{}",
+            self.offset,
+            self.offset + self.length - 1,
+            self.size
+        )
+    }
+}
+
+/// Conditional read options, named after the HTTP headers (R2 `onlyIf`)
+///
+/// Etag lists use header syntax (`"a", W/"b"` or `*`): `if_match` uses the
+/// strong comparison (weak etags never match), `if_none_match` the weak one.
+/// Dates are Unix timestamps in milliseconds, compared with one-second
+/// precision.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conditional {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_match: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_none_match: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_modified_since: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub if_unmodified_since: Option<u64>,
+}
+
+impl Conditional {
+    /// Check if no condition is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Evaluate the conditions against an object (RFC 9110 precedence)
+    pub fn evaluate(&self, metadata: &ObjectMetadata) -> Result<(), ReadRejection> {
+        let uploaded = metadata.uploaded / 1000;
+
+        if let Some(if_match) = &self.if_match {
+            if !etag_matches_strong(if_match, &metadata.etag) {
+                return Err(ReadRejection::PreconditionFailed);
+            }
+        } else if let Some(since) = self.if_unmodified_since
+            && uploaded > since / 1000
+        {
+            return Err(ReadRejection::PreconditionFailed);
+        }
+
+        if let Some(if_none_match) = &self.if_none_match {
+            if etag_matches_weak(if_none_match, &metadata.etag) {
+                return Err(ReadRejection::NotModified);
+            }
+        } else if let Some(since) = self.if_modified_since
+            && uploaded <= since / 1000
+        {
+            return Err(ReadRejection::NotModified);
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for get and fetch operations (R2 `R2GetOptions`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOptions {
+    /// Byte range to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<StorageRange>,
+    /// Conditions that must hold for the body to be returned
+    #[serde(default)]
+    pub only_if: Conditional,
+}
+
+impl GetOptions {
+    /// Read `Range`, `If-Match`, `If-None-Match`, `If-Modified-Since` and
+    /// `If-Unmodified-Since` from request headers (case-insensitive names)
+    pub fn from_headers(headers: &HashMap<String, String>) -> Self {
+        let get = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        let date = |name: &str| {
+            let time = httpdate::parse_http_date(get(name)?).ok()?;
+            let since = time.duration_since(UNIX_EPOCH).ok()?;
+            Some(since.as_millis() as u64)
+        };
+
+        Self {
+            range: get("range").and_then(StorageRange::from_header),
+            only_if: Conditional {
+                if_match: get("if-match").map(str::to_string),
+                if_none_match: get("if-none-match").map(str::to_string),
+                if_modified_since: date("if-modified-since"),
+                if_unmodified_since: date("if-unmodified-since"),
+            },
+        }
+    }
+
+    /// Evaluate conditions then range against an object
+    ///
+    /// Returns the byte range to read (None for the whole object).
+    pub fn evaluate(
+        &self,
+        metadata: &ObjectMetadata,
+    ) -> Result<Option<ContentRange>, ReadRejection> {
+        self.only_if.evaluate(metadata)?;
+
+        match &self.range {
+            Some(range) => match range.resolve(metadata.size) {
+                Some(range) => Ok(Some(range)),
+                None => Err(ReadRejection::RangeNotSatisfiable),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reason a conditional or ranged read returns no body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadRejection {
+    /// `if-none-match` matched or not modified since (HTTP 304)
+    NotModified,
+    /// `if-match` did not match or modified since (HTTP 412)
+    PreconditionFailed,
+    /// Range outside of the object (HTTP 416)
+    RangeNotSatisfiable,
+}
+
+impl ReadRejection {
+    /// HTTP status code for this rejection
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::NotModified => 304,
+            Self::PreconditionFailed => 412,
+            Self::RangeNotSatisfiable => 416,
+        }
+    }
+}
+
+/// Maximum number of keys in a single delete operation
+pub const DELETE_MAX_KEYS: usize = 1000;
+
+/// Optional metadata returned by list operations (R2 `include`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListInclude {
+    HttpMetadata,
+    CustomMetadata,
+}
+
+/// Options for list operations (R2 `R2ListOptions`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListOptions {
+    /// Only list keys starting with this prefix
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Maximum number of objects and delimited prefixes returned
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Group keys containing this string after the prefix (usually `/`)
+    ///
+    /// Such keys are returned once, as the part of the key up to and
+    /// including the delimiter, in `delimited_prefixes`.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// Resume a truncated list (the `cursor` returned with the previous page)
+    ///
+    /// Opaque to callers; the same prefix and delimiter must be passed again.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Metadata to include with listed objects (empty by default)
+    #[serde(default)]
+    pub include: Vec<ListInclude>,
+}
+
+impl ListOptions {
+    /// Prefix `key` is grouped under, if a delimiter follows the list prefix
+    pub fn delimited_prefix<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let delimiter = self.delimiter.as_deref().filter(|d| !d.is_empty())?;
+        let start = self.prefix.as_deref().map_or(0, str::len);
+        let found = key.get(start..)?.find(delimiter)?;
+
+        Some(&key[..start + found + delimiter.len()])
+    }
+
+    /// Drop the metadata not requested by `include`
+    pub fn filter_metadata(&self, mut metadata: ObjectMetadata) -> ObjectMetadata {
+        if !self.include.contains(&ListInclude::HttpMetadata) {
+            metadata.http_metadata = HttpMetadata::default();
+        }
+
+        if !self.include.contains(&ListInclude::CustomMetadata) {
+            metadata.custom_metadata.clear();
+        }
+
+        metadata
+    }
+}
+
+/// Check an etag against an `If-Match` list (strong comparison)
+///
+/// Weak entity tags (`W/"..."`) never match, as required by RFC 9110
+/// section 13.1.1: stored etags are strong.
+fn etag_matches_strong(list: &str, etag: &str) -> bool {
+    list.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || (!candidate.starts_with("W/") && candidate.trim_matches('"') == etag)
+    })
+}
+
+/// Check an etag against an `If-None-Match` list (weak comparison)
+fn etag_matches_weak(list: &str, etag: &str) -> bool {
+    list.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.trim_start_matches("W/").trim_matches('"') == etag
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object() -> ObjectMetadata {
+        ObjectMetadata {
+            key: "key".into(),
+            size: 100,
+            etag: "abc".into(),
+            uploaded: 1_700_000_000_000,
+            ..ObjectMetadata::default()
+        }
+    }
+
+    fn only_if(if_match: Option<&str>, if_none_match: Option<&str>) -> Conditional {
+        Conditional {
+            if_match: if_match.map(Into::into),
+            if_none_match: if_none_match.map(Into::into),
+            ..Conditional::default()
+        }
+    }
+
+    #[test]
+    fn if_match_uses_strong_comparison() {
+        let object = object();
+
+        for list in ["\"abc\"", "abc", "*", "\"x\", \"abc\""] {
+            assert_eq!(
+                only_if(Some(list), None).evaluate(&object),
+                Ok(()),
+                "{}",
+                list
+            );
+        }
+
+        for list in ["W/\"abc\"", "\"x\"", "\"x\", W/\"abc\""] {
+            assert_eq!(
+                only_if(Some(list), None).evaluate(&object),
+                Err(ReadRejection::PreconditionFailed),
+                "{}",
+                list
+            );
+        }
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let object = object();
+
+        for list in ["\"abc\"", "W/\"abc\"", "*"] {
+            assert_eq!(
+                only_if(None, Some(list)).evaluate(&object),
+                Err(ReadRejection::NotModified),
+                "{}",
+                list
+            );
+        }
+
+        assert_eq!(only_if(None, Some("W/\"x\"")).evaluate(&object), Ok(()));
+    }
+
+    #[test]
+    fn if_match_takes_precedence_over_dates() {
+        let conditional = Conditional {
+            if_match: Some("\"abc\"".into()),
+            if_unmodified_since: Some(0),
+            ..Conditional::default()
+        };
+
+        assert_eq!(conditional.evaluate(&object()), Ok(()));
+    }
+
+    #[test]
+    fn ranges_are_resolved_after_conditions() {
+        let object = object();
+        let get = |range, only_if| GetOptions {
+            range: Some(range),
+            only_if,
+        };
+
+        let suffix = get(StorageRange::Suffix { suffix: 10 }, Conditional::default());
+        let expected = ContentRange {
+            offset: 90,
+            length: 10,
+            size: 100,
+        };
+        assert_eq!(suffix.evaluate(&object), Ok(Some(expected)));
+
+        let outside = StorageRange::Offset {
+            offset: 100,
+            length: None,
+        };
+        assert_eq!(
+            get(outside, Conditional::default()).evaluate(&object),
+            Err(ReadRejection::RangeNotSatisfiable)
+        );
+        assert_eq!(
+            get(outside, only_if(None, Some("\"abc\""))).evaluate(&object),
+            Err(ReadRejection::NotModified)
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{HttpRequest, HttpResponse, ResponseSender};
+use crate::{
+    EmailMessage, EmailOutcome, HttpRequest, HttpResponse, QueueBatchResult, QueueMessage,
+    ResponseSender, RpcCall, RpcResult,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tokio::sync::oneshot;
@@ -166,6 +169,126 @@ impl deno_core::Resource for TaskInit {
     }
 }
 
+// ============================================================================
+// Queue Event (batch of queue messages)
+// ============================================================================
+
+/// Queue event initialization data
+#[derive(Debug)]
+pub struct QueueInit {
+    /// Name of the queue the messages come from
+    pub queue: String,
+    /// Messages to process
+    pub messages: Vec<QueueMessage>,
+    /// Channel to send the per-message outcomes back
+    pub res_tx: oneshot::Sender<QueueBatchResult>,
+}
+
+impl QueueInit {
+    pub fn new(
+        queue: String,
+        messages: Vec<QueueMessage>,
+        res_tx: oneshot::Sender<QueueBatchResult>,
+    ) -> Self {
+        Self {
+            queue,
+            messages,
+            res_tx,
+        }
+    }
+}
+
+#[cfg(feature = "deno")]
+impl deno_core::Resource for QueueInit {
+    fn close(self: std::rc::Rc<Self>) {
+        // Resource is being closed, nothing to clean up
+    }
+}
+
+// ============================================================================
+// Alarm Event (durable object alarm)
+// ============================================================================
+
+/// Alarm event initialization data
+#[derive(Debug)]
+pub struct AlarmInit {
+    /// Unix timestamp (ms) the alarm was scheduled for
+    pub scheduled_time: u64,
+    /// Number of previous failed attempts (0 on the first attempt)
+    pub retry_count: u32,
+    /// Channel to send the result back (failed alarms are retried)
+    pub res_tx: oneshot::Sender<TaskResult>,
+}
+
+impl AlarmInit {
+    pub fn new(scheduled_time: u64, retry_count: u32, res_tx: oneshot::Sender<TaskResult>) -> Self {
+        Self {
+            scheduled_time,
+            retry_count,
+            res_tx,
+        }
+    }
+}
+
+#[cfg(feature = "deno")]
+impl deno_core::Resource for AlarmInit {
+    fn close(self: std::rc::Rc<Self>) {
+        // Resource is being closed, nothing to clean up
+    }
+}
+
+// ============================================================================
+// RPC Event (method call from a worker binding)
+// ============================================================================
+
+/// RPC event initialization data
+#[derive(Debug)]
+pub struct RpcInit {
+    /// Method call to dispatch to the worker's exports
+    pub call: RpcCall,
+    /// Channel to send the returned value or thrown error back
+    pub res_tx: oneshot::Sender<RpcResult>,
+}
+
+impl RpcInit {
+    pub fn new(call: RpcCall, res_tx: oneshot::Sender<RpcResult>) -> Self {
+        Self { call, res_tx }
+    }
+}
+
+#[cfg(feature = "deno")]
+impl deno_core::Resource for RpcInit {
+    fn close(self: std::rc::Rc<Self>) {
+        // Resource is being closed, nothing to clean up
+    }
+}
+
+// ============================================================================
+// Email Event (inbound email)
+// ============================================================================
+
+/// Email event initialization data
+#[derive(Debug)]
+pub struct EmailInit {
+    /// Received message with its envelope
+    pub message: EmailMessage,
+    /// Channel to send the outcome back (forward, reject)
+    pub res_tx: oneshot::Sender<EmailOutcome>,
+}
+
+impl EmailInit {
+    pub fn new(message: EmailMessage, res_tx: oneshot::Sender<EmailOutcome>) -> Self {
+        Self { message, res_tx }
+    }
+}
+
+#[cfg(feature = "deno")]
+impl deno_core::Resource for EmailInit {
+    fn close(self: std::rc::Rc<Self>) {
+        // Resource is being closed, nothing to clean up
+    }
+}
+
 // ============================================================================
 // Event Enum (main entry point)
 // ============================================================================
@@ -175,6 +298,10 @@ impl deno_core::Resource for TaskInit {
 pub enum EventType {
     Fetch,
     Task,
+    Queue,
+    Alarm,
+    Rpc,
+    Email,
 }
 
 impl std::fmt::Display for EventType {
@@ -182,6 +309,10 @@ impl std::fmt::Display for EventType {
         match self {
             EventType::Fetch => write!(f, "fetch"),
             EventType::Task => write!(f, "task"),
+            EventType::Queue => write!(f, "queue"),
+            EventType::Alarm => write!(f, "alarm"),
+            EventType::Rpc => write!(f, "rpc"),
+            EventType::Email => write!(f, "email"),
         }
     }
 }
@@ -192,6 +323,14 @@ pub enum Event {
     Fetch(Option<FetchInit>),
     /// Generic task (unified model for scheduled, chained, invoked)
     Task(Option<TaskInit>),
+    /// Batch of queue messages (queue consumer)
+    Queue(Option<QueueInit>),
+    /// Durable object alarm (the worker hosts the object)
+    Alarm(Option<AlarmInit>),
+    /// Method call from a worker binding (RPC)
+    Rpc(Option<RpcInit>),
+    /// Inbound email
+    Email(Option<EmailInit>),
 }
 
 impl Event {
@@ -199,6 +338,10 @@ impl Event {
         match self {
             Event::Fetch(_) => EventType::Fetch,
             Event::Task(_) => EventType::Task,
+            Event::Queue(_) => EventType::Queue,
+            Event::Alarm(_) => EventType::Alarm,
+            Event::Rpc(_) => EventType::Rpc,
+            Event::Email(_) => EventType::Email,
         }
     }
 
@@ -235,4 +378,34 @@ impl Event {
     ) -> (Self, oneshot::Receiver<TaskResult>) {
         Self::task(task_id, payload, Some(TaskSource::Invoke { origin }), 1)
     }
+
+    /// Create a queue event for a batch of messages
+    pub fn queue(
+        queue: String,
+        messages: Vec<QueueMessage>,
+    ) -> (Self, oneshot::Receiver<QueueBatchResult>) {
+        let (tx, rx) = oneshot::channel();
+        (Event::Queue(Some(QueueInit::new(queue, messages, tx))), rx)
+    }
+
+    /// Create an alarm event for a durable object
+    pub fn alarm(scheduled_time: u64, retry_count: u32) -> (Self, oneshot::Receiver<TaskResult>) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Event::Alarm(Some(AlarmInit::new(scheduled_time, retry_count, tx))),
+            rx,
+        )
+    }
+
+    /// Create an RPC event for a method call
+    pub fn rpc(call: RpcCall) -> (Self, oneshot::Receiver<RpcResult>) {
+        let (tx, rx) = oneshot::channel();
+        (Event::Rpc(Some(RpcInit::new(call, tx))), rx)
+    }
+
+    /// Create an email event for a received message
+    pub fn email(message: EmailMessage) -> (Self, oneshot::Receiver<EmailOutcome>) {
+        let (tx, rx) = oneshot::channel();
+        (Event::Email(Some(EmailInit::new(message, tx))), rx)
+    }
 }